@@ -1,36 +1,690 @@
+//! The solver trait, answers, errors and the collections solvers are registered in.
+
+use std::{
+    any::Any, cmp::Ordering, collections::BTreeMap, convert::Infallible, error::Error, fmt,
+    str::FromStr, sync::Arc,
+};
+
+use num::BigInt;
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum PuzzlePart {
     Part1,
     Part2,
 }
 
-pub trait PuzzleSolver {
-    fn solve(&self, input: &mut dyn Iterator<Item = u8>, part: PuzzlePart) -> String;
+impl PuzzlePart {
+    pub const BOTH: [PuzzlePart; 2] = [PuzzlePart::Part1, PuzzlePart::Part2];
+
+    /// `1` or `2`, like the puzzle website numbers the parts.
+    pub fn number(self) -> u8 {
+        match self {
+            PuzzlePart::Part1 => 1,
+            PuzzlePart::Part2 => 2,
+        }
+    }
+}
+
+impl fmt::Display for PuzzlePart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PuzzlePart::Part1 => write!(f, "part 1"),
+            PuzzlePart::Part2 => write!(f, "part 2"),
+        }
+    }
+}
+
+/// The answer to a single puzzle part.
+///
+/// Numeric answers compare by value regardless of the variant, so `Unsigned(5)`
+/// equals `Signed(5)` and `Big(5)`.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Unsigned(u64),
+    Signed(i64),
+    Big(BigInt),
+    Text(String),
+    /// Multi-line text, for example letters drawn on a grid that have to be read by a human.
+    Grid(Vec<String>),
+}
+
+impl Answer {
+    /// Name of the variant as used in machine-readable output.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Answer::Unsigned(_) => "unsigned",
+            Answer::Signed(_) => "signed",
+            Answer::Big(_) => "big",
+            Answer::Text(_) => "text",
+            Answer::Grid(_) => "grid",
+        }
+    }
+
+    /// Like [`Display`](fmt::Display), but the rows of a grid are separated by ` / ` so
+    /// the answer fits into a table.
+    pub fn single_line(&self) -> String {
+        match self {
+            Answer::Grid(rows) => rows.join(" / "),
+            a => a.to_string(),
+        }
+    }
+
+    fn to_big_int(&self) -> Option<BigInt> {
+        match self {
+            Answer::Unsigned(x) => Some(BigInt::from(*x)),
+            Answer::Signed(x) => Some(BigInt::from(*x)),
+            Answer::Big(x) => Some(x.clone()),
+            Answer::Text(_) | Answer::Grid(_) => None,
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Answer::Unsigned(a), Answer::Unsigned(b)) => a == b,
+            (Answer::Signed(a), Answer::Signed(b)) => a == b,
+            (Answer::Text(a), Answer::Text(b)) => a == b,
+            (Answer::Grid(a), Answer::Grid(b)) => a == b,
+            _ => match (self.to_big_int(), other.to_big_int()) {
+                (Some(a), Some(b)) => a == b,
+                _ => false,
+            },
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl PartialOrd for Answer {
+    /// Only numeric answers are ordered, text answers are either equal or unordered.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self.to_big_int(), other.to_big_int()) {
+            (Some(a), Some(b)) => Some(a.cmp(&b)),
+            _ => (self == other).then_some(Ordering::Equal),
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Unsigned(x) => write!(f, "{}", x),
+            Answer::Signed(x) => write!(f, "{}", x),
+            Answer::Big(x) => write!(f, "{}", x),
+            Answer::Text(x) => write!(f, "{}", x),
+            Answer::Grid(rows) => write!(f, "{}", rows.join("\n")),
+        }
+    }
+}
+
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    /// Parses an answer as written in a line of `NN-output.txt`. Integers become the
+    /// smallest fitting numeric variant, everything else is text. The file has one line
+    /// per part, so a grid is never parsed.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(x) = s.parse::<u64>() {
+            return Ok(Answer::Unsigned(x));
+        }
+
+        if let Ok(x) = s.parse::<i64>() {
+            return Ok(Answer::Signed(x));
+        }
+
+        if let Ok(x) = s.parse::<BigInt>() {
+            return Ok(Answer::Big(x));
+        }
+
+        Ok(Answer::Text(s.to_string()))
+    }
+}
+
+macro_rules! answer_from {
+    ($variant:ident, $target:ty, $($source:ty),+) => {
+        $(
+            impl From<$source> for Answer {
+                fn from(value: $source) -> Self {
+                    Answer::$variant(<$target>::from(value))
+                }
+            }
+        )+
+    };
+}
+
+answer_from!(Unsigned, u64, u8, u16, u32, u64);
+answer_from!(Signed, i64, i8, i16, i32, i64);
+answer_from!(Big, BigInt, BigInt);
+answer_from!(Text, String, String, &str);
+answer_from!(Grid, Vec<String>, Vec<String>);
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        match u64::try_from(value) {
+            Ok(x) => Answer::Unsigned(x),
+            Err(_) => Answer::Big(BigInt::from(value)),
+        }
+    }
+}
+
+/// Describes why a puzzle input could not be solved.
+///
+/// Line and column numbers are 1-based. The year, day and part are filled in by
+/// [`DaySolver`], so solvers only need to provide the position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveError {
+    pub year: Option<u16>,
+    pub day: Option<u8>,
+    pub part: Option<PuzzlePart>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub reason: String,
+}
+
+impl SolveError {
+    pub fn new(reason: impl Into<String>) -> Self {
+        Self {
+            year: None,
+            day: None,
+            part: None,
+            line: None,
+            column: None,
+            reason: reason.into(),
+        }
+    }
+
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    pub fn at_column(mut self, column: usize) -> Self {
+        self.column = Some(column);
+        self
+    }
+
+    pub fn in_year(mut self, year: u16) -> Self {
+        self.year = Some(year);
+        self
+    }
+
+    pub fn in_day(mut self, day: u8) -> Self {
+        self.day = Some(day);
+        self
+    }
+
+    pub fn in_puzzle(self, day: u8, part: PuzzlePart) -> Self {
+        let mut error = self.in_day(day);
+        error.part = Some(part);
+        error
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut prefix = vec![];
+
+        if let Some(year) = self.year {
+            prefix.push(year.to_string());
+        }
+
+        if let Some(day) = self.day {
+            prefix.push(format!("day {}", day));
+        }
+
+        if let Some(part) = self.part {
+            prefix.push(part.to_string());
+        }
+
+        if !prefix.is_empty() {
+            write!(f, "{}: ", prefix.join(" "))?;
+        }
+
+        match (self.line, self.column) {
+            (Some(l), Some(c)) => write!(f, "line {}, column {}: ", l, c)?,
+            (Some(l), None) => write!(f, "line {}: ", l)?,
+            _ => {}
+        }
+
+        write!(f, "{}", self.reason)
+    }
+}
+
+impl Error for SolveError {}
+
+/// Solvers are shared between the worker threads of the runner, so they have to be
+/// `Send + Sync`. Stateless unit structs are both automatically.
+pub trait PuzzleSolver: Send + Sync {
+    /// The year of the event this solver belongs to, e.g. `2022`.
+    fn year(&self) -> u16;
+
+    /// The day of the event (`1` to `25`) this solver solves.
+    fn day(&self) -> u8;
+
+    /// Cached answers are only reused while this stays the same. Increase it whenever a
+    /// change to the solver could change its answers.
+    fn version(&self) -> u32 {
+        1
+    }
+
+    /// The parsed puzzle input both parts are solved from.
+    type Model: 'static;
+
+    /// Parses the whole puzzle input. Solvers can either borrow lines from the buffer with
+    /// [`crate::common::SliceLineSplittable`] or iterate its bytes.
+    fn parse(&self, input: &[u8]) -> Result<Self::Model, SolveError>;
+
+    fn part1(&self, model: &Self::Model) -> Result<Answer, SolveError>;
+
+    fn part2(&self, model: &Self::Model) -> Result<Answer, SolveError>;
+}
+
+/// Object safe version of [`PuzzleSolver`] with the model type erased, so solvers
+/// of different days can be stored in the same collection.
+trait ErasedPuzzleSolver: Send + Sync {
+    fn version(&self) -> u32;
+
+    fn parse(&self, input: &[u8]) -> Result<Box<dyn Any>, SolveError>;
+
+    fn solve(&self, model: &dyn Any, part: PuzzlePart) -> Result<Answer, SolveError>;
+}
+
+impl<T: PuzzleSolver> ErasedPuzzleSolver for T {
+    fn version(&self) -> u32 {
+        PuzzleSolver::version(self)
+    }
+
+    fn parse(&self, input: &[u8]) -> Result<Box<dyn Any>, SolveError> {
+        let model = PuzzleSolver::parse(self, input)?;
+        Ok(Box::new(model))
+    }
+
+    fn solve(&self, model: &dyn Any, part: PuzzlePart) -> Result<Answer, SolveError> {
+        let model = model
+            .downcast_ref::<T::Model>()
+            .ok_or_else(|| SolveError::new("parsed input belongs to another solver"))?;
+
+        match part {
+            PuzzlePart::Part1 => self.part1(model),
+            PuzzlePart::Part2 => self.part2(model),
+        }
+    }
+}
+
+/// A puzzle input parsed by the solver of a specific day, ready to solve both parts.
+pub struct ParsedInput {
+    year: u16,
+    day: u8,
+    model: Box<dyn Any>,
+}
+
+impl ParsedInput {
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
 }
 
+/// The solver of a single day. It's cheap to clone and can be moved to other threads,
+/// for example to run it under a timeout.
+#[derive(Clone)]
+pub struct DaySolver {
+    year: u16,
+    day: u8,
+    solver: Arc<dyn ErasedPuzzleSolver>,
+}
+
+impl DaySolver {
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    /// See [`PuzzleSolver::version`].
+    pub fn version(&self) -> u32 {
+        self.solver.version()
+    }
+
+    /// Parses the input once, so both parts can be solved from it with [`Self::solve_parsed`].
+    pub fn parse(&self, input: &[u8]) -> Result<ParsedInput, SolveError> {
+        let model = self
+            .solver
+            .parse(input)
+            .map_err(|e| e.in_year(self.year).in_day(self.day))?;
+
+        Ok(ParsedInput {
+            year: self.year,
+            day: self.day,
+            model,
+        })
+    }
+
+    pub fn solve_parsed(
+        &self,
+        parsed: &ParsedInput,
+        part: PuzzlePart,
+    ) -> Result<Answer, SolveError> {
+        let error_context = |e: SolveError| e.in_year(self.year).in_puzzle(self.day, part);
+
+        if (parsed.year, parsed.day) != (self.year, self.day) {
+            return Err(error_context(SolveError::new(
+                "parsed input belongs to another solver",
+            )));
+        }
+
+        self.solver
+            .solve(parsed.model.as_ref(), part)
+            .map_err(error_context)
+    }
+}
+
+/// All solvers of a single event year, keyed by the day they declare.
 pub struct YearSolverCollection {
-    solvers: Vec<Box<dyn PuzzleSolver>>,
+    year: u16,
+    solvers: BTreeMap<u8, DaySolver>,
 }
 
 impl YearSolverCollection {
-    pub fn new() -> YearSolverCollection {
-        YearSolverCollection { solvers: vec![] }
+    pub fn new(year: u16) -> YearSolverCollection {
+        YearSolverCollection {
+            year,
+            solvers: BTreeMap::new(),
+        }
+    }
+
+    pub fn year(&self) -> u16 {
+        self.year
     }
 
-    pub fn add<T>(&mut self)
+    /// Registers a solver for the day it declares. Days can be added in any order,
+    /// but registering a day twice or a solver of another year is rejected.
+    pub fn add<T>(&mut self) -> Result<(), SolveError>
     where
         T: PuzzleSolver + Default + 'static,
     {
         let solver = T::default();
-        self.solvers.push(Box::new(solver));
+        let day = solver.day();
+
+        if solver.year() != self.year {
+            return Err(SolveError::new(format!(
+                "solver can't be added to the collection of {}",
+                self.year
+            ))
+            .in_year(solver.year())
+            .in_day(day));
+        }
+
+        if !(1..=25).contains(&day) {
+            return Err(SolveError::new("day should be between 1 and 25")
+                .in_year(self.year)
+                .in_day(day));
+        }
+
+        if self.solvers.contains_key(&day) {
+            return Err(SolveError::new("day is already registered")
+                .in_year(self.year)
+                .in_day(day));
+        }
+
+        self.solvers.insert(
+            day,
+            DaySolver {
+                year: self.year,
+                day,
+                solver: Arc::new(solver),
+            },
+        );
+
+        Ok(())
+    }
+
+    /// All registered days in ascending order.
+    pub fn days(&self) -> impl Iterator<Item = u8> + '_ {
+        self.solvers.keys().copied()
+    }
+
+    /// The solvers of all registered days in ascending order.
+    pub fn day_solvers(&self) -> impl Iterator<Item = &DaySolver> + '_ {
+        self.solvers.values()
+    }
+
+    pub fn solver(&self, day: u8) -> Result<&DaySolver, SolveError> {
+        self.solvers.get(&day).ok_or_else(|| {
+            SolveError::new("not implemented")
+                .in_year(self.year)
+                .in_day(day)
+        })
+    }
+
+    /// Parses the input once, so both parts can be solved from it with [`Self::solve_parsed`].
+    pub fn parse(&self, day: u8, input: &[u8]) -> Result<ParsedInput, SolveError> {
+        self.solver(day)?.parse(input)
+    }
+
+    pub fn solve_parsed(
+        &self,
+        parsed: &ParsedInput,
+        part: PuzzlePart,
+    ) -> Result<Answer, SolveError> {
+        if parsed.year != self.year {
+            return Err(SolveError::new("parsed input belongs to another year")
+                .in_year(self.year)
+                .in_puzzle(parsed.day, part));
+        }
+
+        self.solver(parsed.day)
+            .map_err(|e| e.in_puzzle(parsed.day, part))?
+            .solve_parsed(parsed, part)
+    }
+}
+
+/// All year collections, so solvers of several events can be used side by side.
+#[derive(Default)]
+pub struct AocRegistry {
+    years: BTreeMap<u16, YearSolverCollection>,
+}
+
+impl AocRegistry {
+    pub fn new() -> AocRegistry {
+        AocRegistry::default()
+    }
+
+    /// Registers the solvers of a year. Adding the same year twice is rejected.
+    pub fn add_year(&mut self, collection: YearSolverCollection) -> Result<(), SolveError> {
+        let year = collection.year();
+
+        if self.years.contains_key(&year) {
+            return Err(SolveError::new("year is already registered").in_year(year));
+        }
+
+        self.years.insert(year, collection);
+        Ok(())
+    }
+
+    /// The most recent registered year, which is used if no year was specified.
+    pub fn latest_year(&self) -> Option<u16> {
+        self.years.keys().next_back().copied()
+    }
+
+    pub fn year(&self, year: u16) -> Result<&YearSolverCollection, SolveError> {
+        self.years
+            .get(&year)
+            .ok_or_else(|| SolveError::new("not implemented").in_year(year))
+    }
+
+    /// All registered year collections in ascending order.
+    pub fn years(&self) -> impl Iterator<Item = &YearSolverCollection> {
+        self.years.values()
+    }
+
+    pub fn parse(&self, year: u16, day: u8, input: &[u8]) -> Result<ParsedInput, SolveError> {
+        self.year(year)
+            .map_err(|e| e.in_day(day))?
+            .parse(day, input)
+    }
+
+    pub fn solve_parsed(
+        &self,
+        parsed: &ParsedInput,
+        part: PuzzlePart,
+    ) -> Result<Answer, SolveError> {
+        self.year(parsed.year)
+            .map_err(|e| e.in_puzzle(parsed.day, part))?
+            .solve_parsed(parsed, part)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::BigInt;
+
+    use super::{Answer, AocRegistry, PuzzlePart, PuzzleSolver, SolveError, YearSolverCollection};
+
+    macro_rules! fake_solver {
+        ($name:ident, $year:literal, $day:literal) => {
+            #[derive(Default)]
+            struct $name;
+
+            impl PuzzleSolver for $name {
+                fn year(&self) -> u16 {
+                    $year
+                }
+
+                fn day(&self) -> u8 {
+                    $day
+                }
+
+                type Model = usize;
+
+                fn parse(&self, input: &[u8]) -> Result<Self::Model, SolveError> {
+                    Ok(input.len())
+                }
+
+                fn part1(&self, _model: &Self::Model) -> Result<Answer, SolveError> {
+                    Ok(Answer::from($day as u8))
+                }
+
+                fn part2(&self, model: &Self::Model) -> Result<Answer, SolveError> {
+                    Ok(Answer::from(*model))
+                }
+            }
+        };
+    }
+
+    fake_solver!(Day3, 2022, 3);
+    fake_solver!(Day1, 2022, 1);
+    fake_solver!(OtherDay1, 2022, 1);
+    fake_solver!(Day1Of2015, 2015, 1);
+    fake_solver!(Day26, 2022, 26);
+
+    #[test]
+    fn days_can_be_registered_out_of_order_with_gaps() {
+        let mut solvers = YearSolverCollection::new(2022);
+        solvers.add::<Day3>().unwrap();
+        solvers.add::<Day1>().unwrap();
+
+        let solve = |day| {
+            let parsed = solvers.parse(day, &[])?;
+            solvers.solve_parsed(&parsed, PuzzlePart::Part1)
+        };
+
+        assert!(solvers.solver(2).is_err());
+        assert_eq!(Ok(Answer::from(1u8)), solve(1));
+        assert_eq!(Ok(Answer::from(3u8)), solve(3));
+        assert!(solve(2).unwrap_err().reason.starts_with("not implemented"));
+    }
+
+    #[test]
+    fn both_parts_can_be_solved_from_a_single_parse() {
+        let mut solvers = YearSolverCollection::new(2022);
+        solvers.add::<Day1>().unwrap();
+        solvers.add::<Day3>().unwrap();
+
+        let parsed = solvers.parse(3, b"abcd").unwrap();
+
+        assert_eq!(3, parsed.day());
+        assert_eq!(
+            Ok(Answer::from(3u8)),
+            solvers.solve_parsed(&parsed, PuzzlePart::Part1)
+        );
+        assert_eq!(
+            Ok(Answer::from(4u8)),
+            solvers.solve_parsed(&parsed, PuzzlePart::Part2)
+        );
+
+        let mut other_year = YearSolverCollection::new(2015);
+        other_year.add::<Day1Of2015>().unwrap();
+        assert!(other_year.solve_parsed(&parsed, PuzzlePart::Part1).is_err());
+    }
+
+    #[test]
+    fn duplicate_or_foreign_registrations_are_rejected() {
+        let mut solvers = YearSolverCollection::new(2022);
+        solvers.add::<Day1>().unwrap();
+
+        assert!(solvers.add::<OtherDay1>().is_err());
+        assert!(solvers.add::<Day1Of2015>().is_err());
+        assert!(solvers.add::<Day26>().is_err());
+    }
+
+    #[test]
+    fn registry_dispatches_to_the_requested_year() {
+        let mut y2015 = YearSolverCollection::new(2015);
+        y2015.add::<Day1Of2015>().unwrap();
+
+        let mut y2022 = YearSolverCollection::new(2022);
+        y2022.add::<Day3>().unwrap();
+
+        let mut registry = AocRegistry::new();
+        registry.add_year(y2022).unwrap();
+        registry.add_year(y2015).unwrap();
+
+        let solve = |year, day| {
+            let parsed = registry.parse(year, day, &[])?;
+            registry.solve_parsed(&parsed, PuzzlePart::Part1)
+        };
+
+        assert_eq!(Some(2022), registry.latest_year());
+        assert_eq!(Ok(Answer::from(1u8)), solve(2015, 1));
+        assert_eq!(Ok(Answer::from(3u8)), solve(2022, 3));
+        assert_eq!(Some(2016), solve(2016, 1).unwrap_err().year);
+        assert!(registry.add_year(YearSolverCollection::new(2015)).is_err());
+    }
+
+    #[test]
+    fn answers_are_parsed_into_the_smallest_fitting_variant() {
+        assert!(matches!("42".parse(), Ok(Answer::Unsigned(42))));
+        assert!(matches!("-42".parse(), Ok(Answer::Signed(-42))));
+        assert!(matches!("18446744073709551616".parse(), Ok(Answer::Big(_))));
+        assert!(matches!("WHTLRMZRC".parse(), Ok(Answer::Text(_))));
+    }
+
+    #[test]
+    fn numeric_answers_compare_by_value() {
+        assert_eq!(Answer::from(5u32), Answer::from(5i32));
+        assert_eq!(Answer::from(5u32), Answer::Big(BigInt::from(5)));
+        assert_ne!(Answer::from(5u32), Answer::from("5"));
+        assert!(Answer::from(-1) < Answer::from(1u8));
+        assert_eq!(None, Answer::from("a").partial_cmp(&Answer::from("b")));
     }
 
-    pub fn solve(&self, day: u8, input: &mut dyn Iterator<Item = u8>, part: PuzzlePart) -> String {
-        let day = usize::from(day);
-        assert!(day >= 1);
-        assert!(day <= self.solvers.len());
+    #[test]
+    fn answers_display_like_the_output_file() {
+        let grid = Answer::Grid(vec!["#.".to_string(), ".#".to_string()]);
 
-        let index = day - 1;
-        self.solvers[index].solve(input, part)
+        assert_eq!("#.\n.#", grid.to_string());
+        assert_eq!("#. / .#", grid.single_line());
+        assert_eq!("-7", Answer::from(-7).to_string());
+        assert_eq!(
+            Answer::from(123u32),
+            Answer::from(123u32).to_string().parse().unwrap()
+        );
     }
 }
@@ -0,0 +1,277 @@
+//! Repeated timing of reading, parsing and solving the inputs of a data directory.
+
+use std::{
+    fmt, fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crate::aoc_general::{PuzzlePart, SolveError, YearSolverCollection};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Phase {
+    Read,
+    Parse,
+    Part1,
+    Part2,
+}
+
+const PHASES: [Phase; 4] = [Phase::Read, Phase::Parse, Phase::Part1, Phase::Part2];
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Read => write!(f, "read"),
+            Phase::Parse => write!(f, "parse"),
+            Phase::Part1 => write!(f, "part1"),
+            Phase::Part2 => write!(f, "part2"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BenchFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+/// Summary of the durations of repeated runs of a single phase.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    /// Sample standard deviation, zero for a single run.
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// Returns `None` if there are no samples.
+    pub fn from_samples(samples: &[Duration]) -> Option<Stats> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let count = sorted.len();
+
+        let median = if count.is_multiple_of(2) {
+            (sorted[count / 2 - 1] + sorted[count / 2]) / 2
+        } else {
+            sorted[count / 2]
+        };
+
+        let mean = sorted.iter().sum::<Duration>() / count as u32;
+
+        let variance = if count > 1 {
+            sorted
+                .iter()
+                .map(|d| (d.as_secs_f64() - mean.as_secs_f64()).powi(2))
+                .sum::<f64>()
+                / (count - 1) as f64
+        } else {
+            0.0
+        };
+
+        Some(Stats {
+            min: sorted[0],
+            median,
+            mean,
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+pub struct DayBenchmark {
+    pub year: u16,
+    pub day: u8,
+    pub runs: usize,
+    /// Stats for each phase in the order read, parse, part 1, part 2.
+    pub phases: Vec<(Phase, Stats)>,
+}
+
+/// Benchmarks every registered day that has an `NN-input.txt` in `data_dir`.
+/// Days whose input can't be read or solved abort the benchmark with an error.
+pub fn bench_data_dir(
+    solvers: &YearSolverCollection,
+    data_dir: &Path,
+    runs: usize,
+) -> Result<Vec<DayBenchmark>, SolveError> {
+    let mut results = vec![];
+
+    for day in solvers.days() {
+        let input_path = data_dir.join(format!("{day:02}-input.txt"));
+
+        if input_path.is_file() {
+            results.push(bench_day(solvers, day, &input_path, runs)?);
+        }
+    }
+
+    Ok(results)
+}
+
+pub fn bench_day(
+    solvers: &YearSolverCollection,
+    day: u8,
+    input_path: &Path,
+    runs: usize,
+) -> Result<DayBenchmark, SolveError> {
+    let mut samples = PHASES.map(|_| Vec::with_capacity(runs));
+
+    for _ in 0..runs {
+        let start = Instant::now();
+        let input = fs::read(input_path).map_err(|e| {
+            SolveError::new(format!("failed to read '{}': {}", input_path.display(), e))
+                .in_year(solvers.year())
+                .in_day(day)
+        })?;
+        samples[0].push(start.elapsed());
+
+        let start = Instant::now();
+        let parsed = solvers.parse(day, &input)?;
+        samples[1].push(start.elapsed());
+
+        for (i, part) in PuzzlePart::BOTH.into_iter().enumerate() {
+            let start = Instant::now();
+            solvers.solve_parsed(&parsed, part)?;
+            samples[2 + i].push(start.elapsed());
+        }
+    }
+
+    let phases = PHASES
+        .into_iter()
+        .zip(samples.iter())
+        .filter_map(|(phase, s)| Stats::from_samples(s).map(|stats| (phase, stats)))
+        .collect();
+
+    Ok(DayBenchmark {
+        year: solvers.year(),
+        day,
+        runs,
+        phases,
+    })
+}
+
+/// The results as a table, CSV with one line per phase or a JSON array with one object per day.
+pub fn format_results(results: &[DayBenchmark], format: BenchFormat) -> String {
+    match format {
+        BenchFormat::Table => format_table(results),
+        BenchFormat::Json => format_json(results),
+        BenchFormat::Csv => format_csv(results),
+    }
+}
+
+fn format_table(results: &[DayBenchmark]) -> String {
+    let mut table = format!(
+        "year  day  phase  {:>12}  {:>12}  {:>12}  {:>12}\n",
+        "min", "median", "mean", "stddev"
+    );
+
+    for r in results {
+        for (phase, s) in &r.phases {
+            table.push_str(&format!(
+                "{}  {:>3}  {:<5}  {:>12}  {:>12}  {:>12}  {:>12}\n",
+                r.year,
+                r.day,
+                phase,
+                format!("{:.3?}", s.min),
+                format!("{:.3?}", s.median),
+                format!("{:.3?}", s.mean),
+                format!("{:.3?}", s.stddev)
+            ));
+        }
+    }
+
+    table
+}
+
+fn format_csv(results: &[DayBenchmark]) -> String {
+    let mut csv = String::from("year,day,phase,runs,min_ns,median_ns,mean_ns,stddev_ns\n");
+
+    for r in results {
+        for (phase, s) in &r.phases {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{}\n",
+                r.year,
+                r.day,
+                phase,
+                r.runs,
+                s.min.as_nanos(),
+                s.median.as_nanos(),
+                s.mean.as_nanos(),
+                s.stddev.as_nanos()
+            ));
+        }
+    }
+
+    csv
+}
+
+fn format_json(results: &[DayBenchmark]) -> String {
+    let days = results
+        .iter()
+        .map(|r| {
+            let phases = r
+                .phases
+                .iter()
+                .map(|(phase, s)| {
+                    format!(
+                        "\"{}\":{{\"min_ns\":{},\"median_ns\":{},\"mean_ns\":{},\"stddev_ns\":{}}}",
+                        phase,
+                        s.min.as_nanos(),
+                        s.median.as_nanos(),
+                        s.mean.as_nanos(),
+                        s.stddev.as_nanos()
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            format!(
+                "{{\"year\":{},\"day\":{},\"runs\":{},\"phases\":{{{}}}}}",
+                r.year,
+                r.day,
+                r.runs,
+                phases.join(",")
+            )
+        })
+        .collect::<Vec<_>>();
+
+    format!("[{}]\n", days.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Stats;
+
+    #[test]
+    fn stats_of_no_samples_are_none() {
+        assert_eq!(None, Stats::from_samples(&[]));
+    }
+
+    #[test]
+    fn stats_are_calculated_for_unsorted_samples() {
+        let samples = [4, 1, 3, 2].map(Duration::from_millis);
+        let stats = Stats::from_samples(&samples).unwrap();
+
+        assert_eq!(Duration::from_millis(1), stats.min);
+        assert_eq!(Duration::from_micros(2500), stats.median);
+        assert_eq!(Duration::from_micros(2500), stats.mean);
+
+        // sample standard deviation of 1, 2, 3, 4 is sqrt(5/3)
+        let expected_stddev = (5.0f64 / 3.0).sqrt() / 1000.0;
+        assert!((stats.stddev.as_secs_f64() - expected_stddev).abs() < 1e-9);
+    }
+
+    #[test]
+    fn stddev_of_a_single_sample_is_zero() {
+        let stats = Stats::from_samples(&[Duration::from_millis(7)]).unwrap();
+
+        assert_eq!(Duration::from_millis(7), stats.median);
+        assert_eq!(Duration::ZERO, stats.stddev);
+    }
+}
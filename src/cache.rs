@@ -0,0 +1,388 @@
+//! Answers of previous runs stored on disk, keyed by the puzzle, the input and the solver version.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use num::BigInt;
+
+use crate::{
+    aoc_general::{Answer, AocRegistry, DaySolver, PuzzlePart},
+    common,
+    jobs::{InputJob, InputOutcome, PartOutcome, Termination},
+};
+
+/// Used if no `--cache-dir` is given, relative to the working directory.
+pub const DEFAULT_CACHE_DIR: &str = ".aoc-cache";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CacheMode {
+    /// Return cached answers and store new ones.
+    Use,
+    /// Solve everything again and overwrite the cached answers.
+    Refresh,
+    /// Neither read nor write the cache.
+    Disabled,
+}
+
+/// A directory with one file per answer. Only answers of parts that completed without an
+/// error are stored. Entries of older solver versions are ignored and overwritten.
+///
+/// Writing is best effort: if an answer can't be stored it's simply solved again next time.
+pub struct AnswerCache {
+    dir: PathBuf,
+    mode: CacheMode,
+}
+
+/// Summary of the cache directory, see [`AnswerCache::stats`].
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub bytes: u64,
+    /// Entries of a solver version that isn't registered anymore, they are never returned.
+    pub stale: usize,
+    /// Number of entries per year in ascending order.
+    pub years: Vec<(u16, usize)>,
+}
+
+impl AnswerCache {
+    pub fn new(dir: impl Into<PathBuf>, mode: CacheMode) -> AnswerCache {
+        AnswerCache {
+            dir: dir.into(),
+            mode,
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn lookup(&self, solver: &DaySolver, part: PuzzlePart, input: &[u8]) -> Option<Answer> {
+        if self.mode != CacheMode::Use {
+            return None;
+        }
+
+        let content = fs::read_to_string(self.entry_path(solver, part, input)).ok()?;
+        let (version, answer) = parse_entry(&content)?;
+
+        (version == solver.version()).then_some(answer)
+    }
+
+    pub fn store(&self, solver: &DaySolver, part: PuzzlePart, input: &[u8], answer: &Answer) {
+        if self.mode == CacheMode::Disabled {
+            return;
+        }
+
+        let path = self.entry_path(solver, part, input);
+        let content = format!(
+            "version {}\ntype {}\n{}",
+            solver.version(),
+            answer.type_name(),
+            answer
+        );
+
+        // several workers may store the same entry, renaming a finished file is atomic
+        static NEXT_TEMP_ID: AtomicUsize = AtomicUsize::new(0);
+        let temp_path = path.with_extension(format!(
+            "tmp-{}-{}",
+            std::process::id(),
+            NEXT_TEMP_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let written = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&temp_path, content))
+            .and_then(|_| fs::rename(&temp_path, &path));
+
+        if written.is_err() {
+            _ = fs::remove_file(&temp_path);
+        }
+    }
+
+    /// Returns the cached answers of the job and runs it for the other parts, caching their
+    /// successful answers. The input is only parsed if some part isn't cached.
+    pub fn run(&self, job: &InputJob, timeout: Option<Duration>) -> InputOutcome {
+        let cached = job
+            .parts
+            .iter()
+            .map(|&part| self.lookup(&job.solver, part, &job.input))
+            .collect::<Vec<_>>();
+
+        let missing = InputJob {
+            parts: job
+                .parts
+                .iter()
+                .zip(&cached)
+                .filter(|(_, answer)| answer.is_none())
+                .map(|(&part, _)| part)
+                .collect(),
+            ..job.clone()
+        };
+
+        let solved = if missing.parts.is_empty() {
+            InputOutcome {
+                parse_elapsed: Duration::ZERO,
+                parts: vec![],
+            }
+        } else {
+            missing.run(timeout)
+        };
+
+        for (&part, outcome) in missing.parts.iter().zip(&solved.parts) {
+            if let (Termination::Completed, Ok(answer)) = (outcome.termination, &outcome.actual) {
+                self.store(&job.solver, part, &job.input, answer);
+            }
+        }
+
+        let mut solved_parts = solved.parts.into_iter();
+
+        let parts = cached
+            .into_iter()
+            .map(|answer| match answer {
+                Some(answer) => PartOutcome {
+                    actual: Ok(answer),
+                    termination: Termination::Completed,
+                    elapsed: Duration::ZERO,
+                    cached: true,
+                },
+                None => solved_parts
+                    .next()
+                    .expect("there should be an outcome for every part that isn't cached"),
+            })
+            .collect();
+
+        InputOutcome {
+            parse_elapsed: solved.parse_elapsed,
+            parts,
+        }
+    }
+
+    /// Counts the entries in the cache directory, no matter the mode. Versions are compared
+    /// with the solvers in the registry to find stale entries.
+    pub fn stats(&self, registry: &AocRegistry) -> io::Result<CacheStats> {
+        let mut stats = CacheStats::default();
+
+        for year_dir in read_dir_if_exists(&self.dir)? {
+            let year = match common::file_name(&year_dir).parse::<u16>() {
+                Ok(y) if year_dir.is_dir() => y,
+                _ => continue,
+            };
+
+            let mut year_entries = 0;
+
+            for entry in read_dir_if_exists(&year_dir)? {
+                let name = common::file_name(&entry);
+
+                let day = match name.split('-').next().map(str::parse::<u8>) {
+                    Some(Ok(d)) if name.ends_with(".txt") => d,
+                    _ => continue,
+                };
+
+                let content = fs::read_to_string(&entry)?;
+
+                let current_version = registry
+                    .year(year)
+                    .and_then(|s| s.solver(day))
+                    .map(|s| s.version())
+                    .ok();
+
+                if parse_entry(&content).map(|(v, _)| v) != current_version {
+                    stats.stale += 1;
+                }
+
+                stats.entries += 1;
+                stats.bytes += content.len() as u64;
+                year_entries += 1;
+            }
+
+            if year_entries > 0 {
+                stats.years.push((year, year_entries));
+            }
+        }
+
+        stats.years.sort();
+        Ok(stats)
+    }
+
+    /// Removes all cached answers, returns how many there were.
+    pub fn clear(&self) -> io::Result<usize> {
+        let mut removed = 0;
+
+        for year_dir in read_dir_if_exists(&self.dir)? {
+            if year_dir.is_dir() && common::file_name(&year_dir).parse::<u16>().is_ok() {
+                removed += read_dir_if_exists(&year_dir)?.len();
+                fs::remove_dir_all(&year_dir)?;
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// `<dir>/<year>/<day>-part<n>-<input hash>-<input length>.txt`, the length makes hash
+    /// collisions between different inputs even less likely.
+    fn entry_path(&self, solver: &DaySolver, part: PuzzlePart, input: &[u8]) -> PathBuf {
+        let part = part.number();
+
+        self.dir.join(solver.year().to_string()).join(format!(
+            "{:02}-part{}-{}-{}.txt",
+            solver.day(),
+            part,
+            common::input_hash(input),
+            input.len()
+        ))
+    }
+}
+
+/// Reads the solver version and the answer written by [`AnswerCache::store`].
+fn parse_entry(content: &str) -> Option<(u32, Answer)> {
+    let (version, rest) = content.strip_prefix("version ")?.split_once('\n')?;
+    let (answer_type, value) = rest.strip_prefix("type ")?.split_once('\n')?;
+
+    let answer = match answer_type {
+        "unsigned" => Answer::Unsigned(value.parse().ok()?),
+        "signed" => Answer::Signed(value.parse().ok()?),
+        "big" => Answer::Big(value.parse::<BigInt>().ok()?),
+        "text" => Answer::Text(value.to_string()),
+        "grid" => Answer::Grid(value.split('\n').map(String::from).collect()),
+        _ => return None,
+    };
+
+    Some((version.parse().ok()?, answer))
+}
+
+fn read_dir_if_exists(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    match common::sorted_entries(dir) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+        entries => entries,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf, process};
+
+    use super::{parse_entry, AnswerCache, CacheMode};
+    use crate::aoc_general::{
+        Answer, AocRegistry, PuzzlePart, PuzzleSolver, SolveError, YearSolverCollection,
+    };
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-cache-{}-{}", name, process::id()));
+        _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    macro_rules! versioned_solver {
+        ($name:ident, $version:literal) => {
+            #[derive(Default)]
+            struct $name;
+
+            impl PuzzleSolver for $name {
+                fn year(&self) -> u16 {
+                    2022
+                }
+
+                fn day(&self) -> u8 {
+                    1
+                }
+
+                fn version(&self) -> u32 {
+                    $version
+                }
+
+                type Model = ();
+
+                fn parse(&self, _: &[u8]) -> Result<Self::Model, SolveError> {
+                    Ok(())
+                }
+
+                fn part1(&self, _: &Self::Model) -> Result<Answer, SolveError> {
+                    Ok(Answer::Text("0042".into()))
+                }
+
+                fn part2(&self, _: &Self::Model) -> Result<Answer, SolveError> {
+                    Ok(Answer::Grid(vec!["#.".into(), ".#".into()]))
+                }
+            }
+        };
+    }
+
+    versioned_solver!(DayV1, 1);
+    versioned_solver!(DayV2, 2);
+
+    fn registry<T: PuzzleSolver + Default + 'static>() -> AocRegistry {
+        let mut solvers = YearSolverCollection::new(2022);
+        solvers.add::<T>().unwrap();
+
+        let mut registry = AocRegistry::new();
+        registry.add_year(solvers).unwrap();
+        registry
+    }
+
+    #[test]
+    fn entries_keep_the_answer_type() {
+        for answer in [
+            Answer::Unsigned(7),
+            Answer::Signed(-7),
+            Answer::Big("123456789012345678901234567890".parse().unwrap()),
+            Answer::Text("0042".into()),
+            Answer::Grid(vec!["#.".into(), ".#".into()]),
+        ] {
+            let content = format!("version 3\ntype {}\n{}", answer.type_name(), answer);
+            let (version, parsed) = parse_entry(&content).unwrap();
+
+            assert_eq!(3, version);
+            assert_eq!(answer.type_name(), parsed.type_name());
+            assert_eq!(answer, parsed);
+        }
+
+        assert_eq!(
+            None,
+            parse_entry("version 1\ntype unsigned\nx").map(|e| e.0)
+        );
+    }
+
+    #[test]
+    fn answers_of_other_versions_and_inputs_are_not_returned() {
+        let dir = temp_dir("versions");
+        let cache = AnswerCache::new(&dir, CacheMode::Use);
+
+        let v1 = registry::<DayV1>();
+        let v1 = v1.year(2022).unwrap().solver(1).unwrap();
+        let v2 = registry::<DayV2>();
+        let v2_solver = v2.year(2022).unwrap().solver(1).unwrap();
+
+        let answer = Answer::Text("0042".into());
+        cache.store(v1, PuzzlePart::Part1, b"input", &answer);
+
+        assert_eq!(
+            Some(answer.clone()),
+            cache.lookup(v1, PuzzlePart::Part1, b"input")
+        );
+        assert_eq!(None, cache.lookup(v1, PuzzlePart::Part2, b"input"));
+        assert_eq!(None, cache.lookup(v1, PuzzlePart::Part1, b"other"));
+        assert_eq!(None, cache.lookup(v2_solver, PuzzlePart::Part1, b"input"));
+
+        let stats = cache.stats(&v2).unwrap();
+        assert_eq!(
+            (1, 1, vec![(2022, 1)]),
+            (stats.entries, stats.stale, stats.years)
+        );
+
+        let refresh = AnswerCache::new(&dir, CacheMode::Refresh);
+        assert_eq!(None, refresh.lookup(v1, PuzzlePart::Part1, b"input"));
+
+        let disabled = AnswerCache::new(&dir, CacheMode::Disabled);
+        disabled.store(v1, PuzzlePart::Part2, b"input", &answer);
+        assert_eq!(1, disabled.stats(&v2).unwrap().entries);
+
+        assert_eq!(1, cache.clear().unwrap());
+        assert_eq!(0, cache.stats(&v2).unwrap().entries);
+
+        _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,1079 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    path::PathBuf,
+    time::Duration,
+};
+
+use aoc2022::{
+    aoc_general::PuzzlePart,
+    bench::BenchFormat,
+    cache::{AnswerCache, CacheMode, DEFAULT_CACHE_DIR},
+    http::{self, AocWebsite, RateLimiter, Session, DEFAULT_BASE_URL},
+    report::OutputFormat,
+    year_2022::day07::{
+        listing::{ListingFormat, SortOrder},
+        synthesis::{Navigation, SynthesisOptions, Traversal},
+        transcript::Mode,
+    },
+};
+
+pub const USAGE: &str = "\
+Usage: aoc2022 <command> [options]
+
+Commands:
+  solve <day>   Solve a single day
+      --part <1|2|both>   Part to solve (default: both)
+      --input <file>      Read the puzzle input from a file instead of stdin
+      --year <year>       Event year (default: latest registered year)
+      --format <format>   text, json, ndjson or tsv (default: text)
+      --cache             Use cached answers, by default everything is solved again because
+                          cached answers only change with the version of a solver
+      --refresh           Solve again and overwrite the cached answers
+      --no-cache          Neither read nor write cached answers (default)
+      --cache-dir <dir>   Directory of the answer cache (default: .aoc-cache)
+  verify        Check all data/<year>/<user>/NN-output.txt files against the solvers
+      --data <dir>        Data root directory (default: data)
+      --format <format>   text, json, ndjson or tsv (default: text)
+      --jobs <n>          Number of inputs solved in parallel (default: number of CPUs)
+      --timeout <secs>    Give up on parts that run longer, e.g. 2.5 (default: no limit)
+                          (their threads are left running until the command exits)
+      --cache, --refresh, --no-cache, --cache-dir <dir>   Like for solve
+  bench         Measure reading, parsing and both parts of every day with an input
+      --data <dir>        Directory with the NN-input.txt files (required)
+      --runs <n>          Number of runs per day (default: 10)
+      --format <format>   table, json or csv (default: table)
+      --year <year>       Event year (default: latest registered year)
+  run-all       Run both parts of every day with an input and compare the answers
+      --data <dir>        Directory with the NN-input.txt and NN-output.txt files (required)
+      --year <year>       Event year (default: latest registered year)
+      --format <format>   text, json, ndjson or tsv (default: text)
+      --jobs <n>          Number of inputs solved in parallel (default: number of CPUs)
+      --timeout <secs>    Give up on parts that run longer, e.g. 2.5 (default: no limit)
+                          (their threads are left running until the command exits)
+      --cache, --refresh, --no-cache, --cache-dir <dir>   Like for solve
+  fetch <day>   Download the puzzle input to <data>/<year>/<user>/NN-input.txt
+      --user <name>       Directory of the inputs below the year (required)
+      --data <dir>        Data root directory (default: data)
+      --year <year>       Event year (default: latest registered year)
+      --base-url <url>    Website to download from (default: https://adventofcode.com)
+      --session-file <f>  Session token if AOC_SESSION isn't set
+                          (default: ~/.config/aoc2022/session)
+      --cache-dir <dir>   Where the time of the last request is kept (default: .aoc-cache)
+      --request-interval <secs>   Minimum time between two requests (default: 5)
+  submit <day> <part>   Solve a part of <data>/<year>/<user>/NN-input.txt and submit the answer
+                        Answers known to be wrong from submissions.tsv are not submitted,
+                        correct ones are written to NN-output.txt
+      --user, --data, --year, --base-url, --session-file, --cache-dir, --request-interval
+                          Like for fetch
+  new-day <day> Generate src/year_<year>/dayNN.rs with a solver template and register it
+      --year <year>       Event year (default: latest registered year)
+      --src <dir>         Source directory of the crate (default: src)
+  day7 tree     Draw the filesystem of a day 7 transcript with file names and sizes
+  day7 du       List the cumulative size and full path of every folder of a day 7 transcript
+  day7 check    List the inconsistencies of a day 7 transcript with their line numbers
+      --input <file>      Read the transcript from a file instead of stdin
+      --sort <order>      transcript, name or size (default: transcript)
+      --format <format>   text or json (default: text)
+      --strict            Reject inconsistent transcripts instead of counting entries once
+  day7 generate Write a day 7 transcript for the filesystem of a transcript or a local directory
+      --input <file>      Read the transcript from a file instead of stdin
+      --dir <dir>         Scan a local directory instead of reading a transcript
+      --order <order>     depth or breadth, the order of the listed folders (default: depth)
+      --navigation <n>    shortest or root, cd from the root before every ls (default: shortest)
+  cache stats   Show the number and size of cached answers
+  cache clear   Remove all cached answers
+      --cache-dir <dir>   Directory of the answer cache (default: .aoc-cache)
+  list          List all registered years and days
+  help          Show this help
+
+Exit codes:
+  0  success
+  1  a puzzle could not be solved or an answer didn't match
+  2  invalid command line usage
+";
+
+/// Exit code for invalid command line arguments, solver failures use `1`.
+pub const EXIT_USAGE: u8 = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Solve {
+        year: Option<u16>,
+        day: u8,
+        parts: PartSelection,
+        input: Option<PathBuf>,
+        format: OutputFormat,
+        cache: CacheOptions,
+    },
+    Verify {
+        data_root: PathBuf,
+        format: OutputFormat,
+        jobs: Option<usize>,
+        timeout: Option<Duration>,
+        cache: CacheOptions,
+    },
+    Bench {
+        year: Option<u16>,
+        data_dir: PathBuf,
+        runs: usize,
+        format: BenchFormat,
+    },
+    RunAll {
+        year: Option<u16>,
+        data_dir: PathBuf,
+        format: OutputFormat,
+        jobs: Option<usize>,
+        timeout: Option<Duration>,
+        cache: CacheOptions,
+    },
+    Fetch {
+        year: Option<u16>,
+        day: u8,
+        user: String,
+        data_root: PathBuf,
+        website: WebsiteOptions,
+    },
+    Submit {
+        year: Option<u16>,
+        day: u8,
+        part: PuzzlePart,
+        user: String,
+        data_root: PathBuf,
+        website: WebsiteOptions,
+    },
+    Cache {
+        action: CacheAction,
+        dir: PathBuf,
+    },
+    Day7 {
+        action: Day7Action,
+        input: Option<PathBuf>,
+        sort: SortOrder,
+        format: ListingFormat,
+        mode: Mode,
+    },
+    Day7Generate {
+        source: Day7Source,
+        options: SynthesisOptions,
+    },
+    NewDay {
+        year: Option<u16>,
+        day: u8,
+        src_dir: PathBuf,
+    },
+    List,
+    Help,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Day7Action {
+    Tree,
+    Du,
+    Check,
+}
+
+/// Where `day7 generate` takes the filesystem from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Day7Source {
+    /// A transcript file or stdin.
+    Transcript(Option<PathBuf>),
+    Directory(PathBuf),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CacheAction {
+    Stats,
+    Clear,
+}
+
+/// `--cache`, `--refresh`, `--no-cache` and `--cache-dir` of the commands that solve puzzles.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheOptions {
+    pub dir: PathBuf,
+    pub mode: CacheMode,
+}
+
+impl CacheOptions {
+    pub fn open(&self) -> AnswerCache {
+        AnswerCache::new(&self.dir, self.mode)
+    }
+}
+
+/// The cache is only used with `--cache` or `--refresh`.
+const CACHE_FLAGS: [&str; 3] = ["--cache", "--no-cache", "--refresh"];
+
+/// `--base-url`, `--session-file`, `--cache-dir` and `--request-interval` of the commands that
+/// make requests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebsiteOptions {
+    pub base_url: String,
+    pub session_file: Option<PathBuf>,
+    /// The time of the last request is kept here for the rate limit.
+    pub cache_dir: PathBuf,
+    pub request_interval: Duration,
+}
+
+impl WebsiteOptions {
+    pub fn open(&self) -> AocWebsite {
+        let session_file = self
+            .session_file
+            .clone()
+            .or_else(http::default_session_file);
+
+        AocWebsite::new(
+            &self.base_url,
+            Session::Lookup(session_file),
+            RateLimiter::new(self.cache_dir.join("last-request"), self.request_interval),
+        )
+    }
+}
+
+/// The options of `fetch` and `submit`.
+const WEBSITE_COMMAND_OPTIONS: [&str; 7] = [
+    "--user",
+    "--data",
+    "--year",
+    "--base-url",
+    "--session-file",
+    "--cache-dir",
+    "--request-interval",
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PartSelection {
+    Part1,
+    Part2,
+    Both,
+}
+
+impl PartSelection {
+    pub fn parts(self) -> Vec<PuzzlePart> {
+        match self {
+            PartSelection::Part1 => vec![PuzzlePart::Part1],
+            PartSelection::Part2 => vec![PuzzlePart::Part2],
+            PartSelection::Both => PuzzlePart::BOTH.to_vec(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageError(pub String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Parses the command line arguments (without the program name).
+pub fn parse_args<I>(args: I) -> Result<Command, UsageError>
+where
+    I: IntoIterator<Item = String>,
+{
+    let args = args.into_iter().collect::<Vec<_>>();
+
+    let (command, rest) = match args.split_first() {
+        None => return Ok(Command::Help),
+        Some((c, rest)) => (c.as_str(), rest),
+    };
+
+    if rest.iter().any(|a| a == "--help" || a == "-h") {
+        return Ok(Command::Help);
+    }
+
+    match command {
+        "solve" => {
+            let mut args = ParsedArgs::parse(
+                rest,
+                &["--part", "--input", "--year", "--format", "--cache-dir"],
+                &CACHE_FLAGS,
+            )?;
+            let day = parse_day(&args.single_positional("day")?)?;
+
+            Ok(Command::Solve {
+                year: args.take_parsed("--year", parse_year)?,
+                day,
+                parts: args
+                    .take_parsed("--part", parse_part_selection)?
+                    .unwrap_or(PartSelection::Both),
+                input: args.take("--input").map(PathBuf::from),
+                format: args.take_output_format()?,
+                cache: args.take_cache_options()?,
+            })
+        }
+        "verify" => {
+            let mut args = ParsedArgs::parse(
+                rest,
+                &["--data", "--format", "--jobs", "--timeout", "--cache-dir"],
+                &CACHE_FLAGS,
+            )?;
+            args.no_positionals()?;
+
+            Ok(Command::Verify {
+                data_root: PathBuf::from(args.take("--data").unwrap_or_else(|| "data".into())),
+                format: args.take_output_format()?,
+                jobs: args.take_parsed("--jobs", parse_jobs)?,
+                timeout: args.take_parsed("--timeout", parse_timeout)?,
+                cache: args.take_cache_options()?,
+            })
+        }
+        "bench" => {
+            let mut args =
+                ParsedArgs::parse(rest, &["--data", "--runs", "--format", "--year"], &[])?;
+            args.no_positionals()?;
+
+            Ok(Command::Bench {
+                year: args.take_parsed("--year", parse_year)?,
+                data_dir: args.require("--data").map(PathBuf::from)?,
+                runs: args.take_parsed("--runs", parse_runs)?.unwrap_or(10),
+                format: args
+                    .take_parsed("--format", parse_bench_format)?
+                    .unwrap_or(BenchFormat::Table),
+            })
+        }
+        "run-all" => {
+            let mut args = ParsedArgs::parse(
+                rest,
+                &[
+                    "--data",
+                    "--year",
+                    "--format",
+                    "--jobs",
+                    "--timeout",
+                    "--cache-dir",
+                ],
+                &CACHE_FLAGS,
+            )?;
+            args.no_positionals()?;
+
+            Ok(Command::RunAll {
+                year: args.take_parsed("--year", parse_year)?,
+                data_dir: args.require("--data").map(PathBuf::from)?,
+                format: args.take_output_format()?,
+                jobs: args.take_parsed("--jobs", parse_jobs)?,
+                timeout: args.take_parsed("--timeout", parse_timeout)?,
+                cache: args.take_cache_options()?,
+            })
+        }
+        "fetch" => {
+            let mut args = ParsedArgs::parse(rest, &WEBSITE_COMMAND_OPTIONS, &[])?;
+            let day = parse_day(&args.single_positional("day")?)?;
+
+            Ok(Command::Fetch {
+                year: args.take_parsed("--year", parse_year)?,
+                day,
+                user: args.require("--user")?,
+                data_root: PathBuf::from(args.take("--data").unwrap_or_else(|| "data".into())),
+                website: args.take_website_options()?,
+            })
+        }
+        "submit" => {
+            let mut args = ParsedArgs::parse(rest, &WEBSITE_COMMAND_OPTIONS, &[])?;
+            let [day, part] = args.positionals(["day", "part"])?;
+
+            Ok(Command::Submit {
+                year: args.take_parsed("--year", parse_year)?,
+                day: parse_day(&day)?,
+                part: parse_part(&part)?,
+                user: args.require("--user")?,
+                data_root: PathBuf::from(args.take("--data").unwrap_or_else(|| "data".into())),
+                website: args.take_website_options()?,
+            })
+        }
+        "cache" => {
+            let mut args = ParsedArgs::parse(rest, &["--cache-dir"], &[])?;
+
+            let action = match args.single_positional("stats|clear")?.as_str() {
+                "stats" => CacheAction::Stats,
+                "clear" => CacheAction::Clear,
+                x => {
+                    return Err(UsageError(format!(
+                        "unknown cache action '{}', expected stats or clear",
+                        x
+                    )))
+                }
+            };
+
+            Ok(Command::Cache {
+                action,
+                dir: args.take_cache_dir(),
+            })
+        }
+        "day7" if rest.first().map(String::as_str) == Some("generate") => {
+            let mut args = ParsedArgs::parse(
+                &rest[1..],
+                &["--input", "--dir", "--order", "--navigation"],
+                &[],
+            )?;
+            args.no_positionals()?;
+
+            let source = match (args.take("--input"), args.take("--dir")) {
+                (Some(_), Some(_)) => {
+                    return Err(UsageError("--input and --dir can't be combined".into()))
+                }
+                (None, Some(dir)) => Day7Source::Directory(PathBuf::from(dir)),
+                (input, None) => Day7Source::Transcript(input.map(PathBuf::from)),
+            };
+
+            Ok(Command::Day7Generate {
+                source,
+                options: SynthesisOptions {
+                    traversal: args
+                        .take_parsed("--order", parse_traversal)?
+                        .unwrap_or(Traversal::DepthFirst),
+                    navigation: args
+                        .take_parsed("--navigation", parse_navigation)?
+                        .unwrap_or(Navigation::Shortest),
+                },
+            })
+        }
+        "day7" => {
+            let mut args =
+                ParsedArgs::parse(rest, &["--input", "--sort", "--format"], &["--strict"])?;
+
+            let action = match args.single_positional("tree|du|check")?.as_str() {
+                "tree" => Day7Action::Tree,
+                "du" => Day7Action::Du,
+                "check" => Day7Action::Check,
+                x => {
+                    return Err(UsageError(format!(
+                        "unknown day7 action '{}', expected tree, du or check",
+                        x
+                    )))
+                }
+            };
+
+            Ok(Command::Day7 {
+                action,
+                input: args.take("--input").map(PathBuf::from),
+                sort: args
+                    .take_parsed("--sort", parse_sort_order)?
+                    .unwrap_or(SortOrder::Transcript),
+                format: args
+                    .take_parsed("--format", parse_listing_format)?
+                    .unwrap_or(ListingFormat::Text),
+                mode: if args.take_flag("--strict") {
+                    Mode::Strict
+                } else {
+                    Mode::Lenient
+                },
+            })
+        }
+        "new-day" => {
+            let mut args = ParsedArgs::parse(rest, &["--year", "--src"], &[])?;
+            let day = parse_day(&args.single_positional("day")?)?;
+
+            Ok(Command::NewDay {
+                year: args.take_parsed("--year", parse_year)?,
+                day,
+                src_dir: PathBuf::from(args.take("--src").unwrap_or_else(|| "src".into())),
+            })
+        }
+        "list" => {
+            ParsedArgs::parse(rest, &[], &[])?.no_positionals()?;
+            Ok(Command::List)
+        }
+        "help" | "--help" | "-h" => Ok(Command::Help),
+        x => Err(UsageError(format!("unknown command '{}'", x))),
+    }
+}
+
+pub fn parse_day(value: &str) -> Result<u8, UsageError> {
+    value
+        .parse::<u8>()
+        .ok()
+        .filter(|d| (1..=25).contains(d))
+        .ok_or_else(|| UsageError(format!("'{}' is not a day between 1 and 25", value)))
+}
+
+pub fn parse_year(value: &str) -> Result<u16, UsageError> {
+    value
+        .parse::<u16>()
+        .map_err(|_| UsageError(format!("'{}' is not a valid year", value)))
+}
+
+fn parse_runs(value: &str) -> Result<usize, UsageError> {
+    value
+        .parse::<usize>()
+        .ok()
+        .filter(|&r| r > 0)
+        .ok_or_else(|| UsageError(format!("'{}' is not a positive number of runs", value)))
+}
+
+fn parse_jobs(value: &str) -> Result<usize, UsageError> {
+    value
+        .parse::<usize>()
+        .ok()
+        .filter(|&j| j > 0)
+        .ok_or_else(|| UsageError(format!("'{}' is not a positive number of jobs", value)))
+}
+
+fn parse_timeout(value: &str) -> Result<Duration, UsageError> {
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|&t| t > 0.0)
+        .and_then(|t| Duration::try_from_secs_f64(t).ok())
+        .ok_or_else(|| UsageError(format!("'{}' is not a positive number of seconds", value)))
+}
+
+fn parse_interval(value: &str) -> Result<Duration, UsageError> {
+    value
+        .parse::<f64>()
+        .ok()
+        .and_then(|t| Duration::try_from_secs_f64(t).ok())
+        .ok_or_else(|| UsageError(format!("'{}' is not a number of seconds", value)))
+}
+
+fn parse_base_url(value: &str) -> Result<String, UsageError> {
+    if value.starts_with("http://") || value.starts_with("https://") {
+        Ok(value.to_string())
+    } else {
+        Err(UsageError(format!(
+            "'{}' is not a valid base URL, expected http:// or https://",
+            value
+        )))
+    }
+}
+
+fn parse_bench_format(value: &str) -> Result<BenchFormat, UsageError> {
+    match value {
+        "table" => Ok(BenchFormat::Table),
+        "json" => Ok(BenchFormat::Json),
+        "csv" => Ok(BenchFormat::Csv),
+        x => Err(UsageError(format!(
+            "'{}' is not a valid format, expected table, json or csv",
+            x
+        ))),
+    }
+}
+
+fn parse_sort_order(value: &str) -> Result<SortOrder, UsageError> {
+    match value {
+        "transcript" => Ok(SortOrder::Transcript),
+        "name" => Ok(SortOrder::Name),
+        "size" => Ok(SortOrder::Size),
+        x => Err(UsageError(format!(
+            "'{}' is not a valid sort order, expected transcript, name or size",
+            x
+        ))),
+    }
+}
+
+fn parse_listing_format(value: &str) -> Result<ListingFormat, UsageError> {
+    match value {
+        "text" => Ok(ListingFormat::Text),
+        "json" => Ok(ListingFormat::Json),
+        x => Err(UsageError(format!(
+            "'{}' is not a valid format, expected text or json",
+            x
+        ))),
+    }
+}
+
+fn parse_traversal(value: &str) -> Result<Traversal, UsageError> {
+    match value {
+        "depth" => Ok(Traversal::DepthFirst),
+        "breadth" => Ok(Traversal::BreadthFirst),
+        x => Err(UsageError(format!(
+            "'{}' is not a valid order, expected depth or breadth",
+            x
+        ))),
+    }
+}
+
+fn parse_navigation(value: &str) -> Result<Navigation, UsageError> {
+    match value {
+        "shortest" => Ok(Navigation::Shortest),
+        "root" => Ok(Navigation::FromRoot),
+        x => Err(UsageError(format!(
+            "'{}' is not a valid navigation, expected shortest or root",
+            x
+        ))),
+    }
+}
+
+fn parse_output_format(value: &str) -> Result<OutputFormat, UsageError> {
+    match value {
+        "text" => Ok(OutputFormat::Text),
+        "json" => Ok(OutputFormat::Json),
+        "ndjson" => Ok(OutputFormat::Ndjson),
+        "tsv" => Ok(OutputFormat::Tsv),
+        x => Err(UsageError(format!(
+            "'{}' is not a valid format, expected text, json, ndjson or tsv",
+            x
+        ))),
+    }
+}
+
+fn parse_part(value: &str) -> Result<PuzzlePart, UsageError> {
+    match value {
+        "1" => Ok(PuzzlePart::Part1),
+        "2" => Ok(PuzzlePart::Part2),
+        x => Err(UsageError(format!(
+            "'{}' is not a valid part, expected 1 or 2",
+            x
+        ))),
+    }
+}
+
+fn parse_part_selection(value: &str) -> Result<PartSelection, UsageError> {
+    match value {
+        "1" => Ok(PartSelection::Part1),
+        "2" => Ok(PartSelection::Part2),
+        "both" => Ok(PartSelection::Both),
+        x => Err(UsageError(format!(
+            "'{}' is not a valid part, expected 1, 2 or both",
+            x
+        ))),
+    }
+}
+
+/// Arguments of a single command split into positionals, options with a value and flags.
+struct ParsedArgs {
+    positionals: Vec<String>,
+    options: HashMap<String, String>,
+    flags: HashSet<String>,
+}
+
+impl ParsedArgs {
+    /// Options can be written as `--name value` or `--name=value`, flags don't take a value.
+    fn parse(args: &[String], options: &[&str], flags: &[&str]) -> Result<Self, UsageError> {
+        let mut parsed = ParsedArgs {
+            positionals: vec![],
+            options: HashMap::new(),
+            flags: HashSet::new(),
+        };
+
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            if !arg.starts_with("--") {
+                parsed.positionals.push(arg.clone());
+                continue;
+            }
+
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((n, v)) => (n, Some(v.to_string())),
+                None => (arg.as_str(), None),
+            };
+
+            if flags.contains(&name) {
+                if inline_value.is_some() {
+                    return Err(UsageError(format!("flag '{}' doesn't take a value", name)));
+                }
+
+                if !parsed.flags.insert(name.to_string()) {
+                    return Err(UsageError(format!("flag '{}' given more than once", name)));
+                }
+            } else if options.contains(&name) {
+                let value = inline_value
+                    .or_else(|| iter.next().cloned())
+                    .ok_or_else(|| UsageError(format!("option '{}' requires a value", name)))?;
+
+                if parsed.options.insert(name.to_string(), value).is_some() {
+                    return Err(UsageError(format!(
+                        "option '{}' given more than once",
+                        name
+                    )));
+                }
+            } else {
+                return Err(UsageError(format!("unknown option '{}'", name)));
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    fn take(&mut self, name: &str) -> Option<String> {
+        self.options.remove(name)
+    }
+
+    fn take_flag(&mut self, name: &str) -> bool {
+        self.flags.remove(name)
+    }
+
+    fn require(&mut self, name: &str) -> Result<String, UsageError> {
+        self.take(name)
+            .ok_or_else(|| UsageError(format!("missing option '{}'", name)))
+    }
+
+    fn take_parsed<T, F>(&mut self, name: &str, parse: F) -> Result<Option<T>, UsageError>
+    where
+        F: Fn(&str) -> Result<T, UsageError>,
+    {
+        self.take(name).map(|v| parse(&v)).transpose()
+    }
+
+    /// The `--format` option of the commands that report puzzle answers.
+    fn take_output_format(&mut self) -> Result<OutputFormat, UsageError> {
+        Ok(self
+            .take_parsed("--format", parse_output_format)?
+            .unwrap_or(OutputFormat::Text))
+    }
+
+    fn take_cache_dir(&mut self) -> PathBuf {
+        PathBuf::from(
+            self.take("--cache-dir")
+                .unwrap_or_else(|| DEFAULT_CACHE_DIR.into()),
+        )
+    }
+
+    /// The cache is disabled if none of `--cache`, `--no-cache` and `--refresh` is given.
+    fn take_cache_options(&mut self) -> Result<CacheOptions, UsageError> {
+        let modes = [
+            ("--cache", CacheMode::Use),
+            ("--no-cache", CacheMode::Disabled),
+            ("--refresh", CacheMode::Refresh),
+        ]
+        .into_iter()
+        .filter(|(flag, _)| self.take_flag(flag))
+        .collect::<Vec<_>>();
+
+        let mode = match modes[..] {
+            [] => CacheMode::Disabled,
+            [(_, mode)] => mode,
+            [(first, _), (second, _), ..] => {
+                return Err(UsageError(format!(
+                    "'{}' and '{}' can't be combined",
+                    first, second
+                )))
+            }
+        };
+
+        Ok(CacheOptions {
+            dir: self.take_cache_dir(),
+            mode,
+        })
+    }
+
+    fn take_website_options(&mut self) -> Result<WebsiteOptions, UsageError> {
+        Ok(WebsiteOptions {
+            base_url: self
+                .take_parsed("--base-url", parse_base_url)?
+                .unwrap_or_else(|| DEFAULT_BASE_URL.into()),
+            session_file: self.take("--session-file").map(PathBuf::from),
+            cache_dir: self.take_cache_dir(),
+            request_interval: self
+                .take_parsed("--request-interval", parse_interval)?
+                .unwrap_or(http::REQUEST_INTERVAL),
+        })
+    }
+
+    fn single_positional(&mut self, what: &str) -> Result<String, UsageError> {
+        let [value] = self.positionals([what])?;
+        Ok(value)
+    }
+
+    /// Exactly `N` positionals, `names` are used in the error message if some are missing.
+    fn positionals<const N: usize>(&mut self, names: [&str; N]) -> Result<[String; N], UsageError> {
+        if let Some(x) = self.positionals.get(N) {
+            return Err(UsageError(format!("unexpected argument '{}'", x)));
+        }
+
+        if let Some(missing) = names.get(self.positionals.len()) {
+            return Err(UsageError(format!("missing argument <{}>", missing)));
+        }
+
+        Ok(std::array::from_fn(|i| {
+            std::mem::take(&mut self.positionals[i])
+        }))
+    }
+
+    fn no_positionals(&self) -> Result<(), UsageError> {
+        match self.positionals.first() {
+            None => Ok(()),
+            Some(x) => Err(UsageError(format!("unexpected argument '{}'", x))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, time::Duration};
+
+    use super::{
+        parse_args, CacheAction, CacheOptions, Command, Day7Action, Day7Source, PartSelection,
+        WebsiteOptions,
+    };
+    use aoc2022::{
+        aoc_general::PuzzlePart,
+        bench::BenchFormat,
+        cache::CacheMode,
+        report::OutputFormat,
+        year_2022::day07::{
+            listing::{ListingFormat, SortOrder},
+            synthesis::{Navigation, SynthesisOptions, Traversal},
+            transcript::Mode,
+        },
+    };
+
+    fn parse(args: &str) -> Result<Command, super::UsageError> {
+        parse_args(args.split_whitespace().map(String::from))
+    }
+
+    fn default_cache() -> CacheOptions {
+        CacheOptions {
+            dir: PathBuf::from(".aoc-cache"),
+            mode: CacheMode::Disabled,
+        }
+    }
+
+    #[test]
+    fn solve_uses_defaults_for_missing_options() {
+        let expected = Command::Solve {
+            year: None,
+            day: 7,
+            parts: PartSelection::Both,
+            input: None,
+            format: OutputFormat::Text,
+            cache: default_cache(),
+        };
+
+        assert_eq!(Ok(expected), parse("solve 7"));
+    }
+
+    #[test]
+    fn solve_accepts_options_in_both_notations() {
+        let expected = Command::Solve {
+            year: Some(2022),
+            day: 3,
+            parts: PartSelection::Part2,
+            input: Some(PathBuf::from("in.txt")),
+            format: OutputFormat::Ndjson,
+            cache: default_cache(),
+        };
+
+        assert_eq!(
+            Ok(expected),
+            parse("solve --part=2 3 --input in.txt --year 2022 --format=ndjson")
+        );
+    }
+
+    #[test]
+    fn bench_requires_a_data_directory() {
+        let expected = Command::Bench {
+            year: None,
+            data_dir: PathBuf::from("data/2022/examples"),
+            runs: 3,
+            format: BenchFormat::Csv,
+        };
+
+        assert_eq!(
+            Ok(expected),
+            parse("bench --data data/2022/examples --runs 3 --format csv")
+        );
+        assert!(parse("bench --runs 3").is_err());
+        assert!(parse("bench --data x --runs 0").is_err());
+    }
+
+    #[test]
+    fn run_all_requires_a_data_directory() {
+        let expected = Command::RunAll {
+            year: Some(2022),
+            data_dir: PathBuf::from("data/2022/wischi-chr"),
+            format: OutputFormat::Text,
+            jobs: Some(4),
+            timeout: Some(Duration::from_millis(2500)),
+            cache: default_cache(),
+        };
+
+        assert_eq!(
+            Ok(expected),
+            parse("run-all --year 2022 --data=data/2022/wischi-chr --jobs 4 --timeout 2.5")
+        );
+        assert!(parse("run-all").is_err());
+        assert!(parse("run-all --data x 3").is_err());
+        assert!(parse("run-all --data x --jobs 0").is_err());
+        assert!(parse("run-all --data x --timeout -1").is_err());
+        assert!(parse("run-all --data x --timeout NaN").is_err());
+    }
+
+    #[test]
+    fn cache_flags_select_the_cache_mode() {
+        let verify = |mode, dir: &str| Command::Verify {
+            data_root: PathBuf::from("data"),
+            format: OutputFormat::Text,
+            jobs: None,
+            timeout: None,
+            cache: CacheOptions {
+                dir: PathBuf::from(dir),
+                mode,
+            },
+        };
+
+        assert_eq!(
+            Ok(verify(CacheMode::Disabled, ".aoc-cache")),
+            parse("verify")
+        );
+        assert_eq!(
+            Ok(verify(CacheMode::Use, ".aoc-cache")),
+            parse("verify --cache")
+        );
+        assert_eq!(
+            Ok(verify(CacheMode::Refresh, "/tmp/c")),
+            parse("verify --refresh --cache-dir /tmp/c")
+        );
+        assert_eq!(
+            Ok(Command::Cache {
+                action: CacheAction::Clear,
+                dir: PathBuf::from("x"),
+            }),
+            parse("cache clear --cache-dir=x")
+        );
+
+        assert!(parse("verify --no-cache --refresh").is_err());
+        assert!(parse("verify --cache --refresh").is_err());
+        assert!(parse("run-all --data x --cache --no-cache").is_err());
+        assert!(parse("verify --refresh --refresh").is_err());
+        assert!(parse("verify --no-cache=yes").is_err());
+        assert!(parse("bench --data x --no-cache").is_err());
+        assert!(parse("cache").is_err());
+        assert!(parse("cache purge").is_err());
+    }
+
+    #[test]
+    fn fetch_requires_a_user() {
+        let expected = Command::Fetch {
+            year: None,
+            day: 5,
+            user: "someone".into(),
+            data_root: PathBuf::from("data"),
+            website: WebsiteOptions {
+                base_url: "http://127.0.0.1:8080".into(),
+                session_file: Some(PathBuf::from("token.txt")),
+                cache_dir: PathBuf::from(".aoc-cache"),
+                request_interval: Duration::ZERO,
+            },
+        };
+
+        assert_eq!(
+            Ok(expected),
+            parse(
+                "fetch 5 --user someone --base-url http://127.0.0.1:8080 --session-file token.txt --request-interval 0"
+            )
+        );
+        assert!(parse("fetch 5").is_err());
+        assert!(parse("fetch --user someone").is_err());
+        assert!(parse("fetch 5 --user someone --base-url ftp://x").is_err());
+        assert!(parse("fetch 5 --user someone --request-interval -1").is_err());
+    }
+
+    #[test]
+    fn submit_takes_a_day_and_a_part() {
+        let expected = Command::Submit {
+            year: Some(2022),
+            day: 3,
+            part: PuzzlePart::Part2,
+            user: "someone".into(),
+            data_root: PathBuf::from("data"),
+            website: WebsiteOptions {
+                base_url: "https://adventofcode.com".into(),
+                session_file: None,
+                cache_dir: PathBuf::from(".aoc-cache"),
+                request_interval: Duration::from_secs(5),
+            },
+        };
+
+        assert_eq!(Ok(expected), parse("submit 3 2 --user someone --year 2022"));
+        assert!(parse("submit 3 --user someone").is_err());
+        assert!(parse("submit 3 both --user someone").is_err());
+        assert!(parse("submit 3 1 1 --user someone").is_err());
+    }
+
+    #[test]
+    fn day7_takes_an_action_and_listing_options() {
+        assert_eq!(
+            Ok(Command::Day7 {
+                action: Day7Action::Du,
+                input: Some(PathBuf::from("07.txt")),
+                sort: SortOrder::Size,
+                format: ListingFormat::Json,
+                mode: Mode::Strict,
+            }),
+            parse("day7 du --input 07.txt --sort size --format json --strict")
+        );
+        assert_eq!(
+            Ok(Command::Day7 {
+                action: Day7Action::Tree,
+                input: None,
+                sort: SortOrder::Transcript,
+                format: ListingFormat::Text,
+                mode: Mode::Lenient,
+            }),
+            parse("day7 tree")
+        );
+        assert!(parse("day7").is_err());
+        assert!(parse("day7 ls").is_err());
+        assert!(parse("day7 tree --sort date").is_err());
+        assert!(parse("day7 du --format tsv").is_err());
+    }
+
+    #[test]
+    fn day7_generate_reads_a_transcript_or_a_directory() {
+        assert_eq!(
+            Ok(Command::Day7Generate {
+                source: Day7Source::Transcript(None),
+                options: SynthesisOptions::default(),
+            }),
+            parse("day7 generate")
+        );
+        assert_eq!(
+            Ok(Command::Day7Generate {
+                source: Day7Source::Directory(PathBuf::from("/tmp/x")),
+                options: SynthesisOptions {
+                    traversal: Traversal::BreadthFirst,
+                    navigation: Navigation::FromRoot,
+                },
+            }),
+            parse("day7 generate --dir /tmp/x --order breadth --navigation root")
+        );
+        assert!(parse("day7 generate --dir x --input y").is_err());
+        assert!(parse("day7 generate --order random").is_err());
+        assert!(parse("day7 generate --sort size").is_err());
+        assert!(parse("day7 generate x").is_err());
+    }
+
+    #[test]
+    fn new_day_takes_a_day_and_a_source_directory() {
+        assert_eq!(
+            Ok(Command::NewDay {
+                year: None,
+                day: 9,
+                src_dir: PathBuf::from("src"),
+            }),
+            parse("new-day 9")
+        );
+        assert_eq!(
+            Ok(Command::NewDay {
+                year: Some(2023),
+                day: 1,
+                src_dir: PathBuf::from("/tmp/src"),
+            }),
+            parse("new-day 1 --year 2023 --src /tmp/src")
+        );
+        assert!(parse("new-day").is_err());
+        assert!(parse("new-day 26").is_err());
+    }
+
+    #[test]
+    fn invalid_arguments_are_usage_errors() {
+        assert!(parse("solve").is_err());
+        assert!(parse("solve 26").is_err());
+        assert!(parse("solve 3 4").is_err());
+        assert!(parse("solve 3 --part 3").is_err());
+        assert!(parse("solve 3 --input").is_err());
+        assert!(parse("solve 3 --unknown x").is_err());
+        assert!(parse("verify extra").is_err());
+        assert!(parse("verify --format csv").is_err());
+        assert!(parse("frobnicate").is_err());
+    }
+
+    #[test]
+    fn help_is_shown_without_arguments_or_when_asked_for() {
+        assert_eq!(Ok(Command::Help), parse(""));
+        assert_eq!(Ok(Command::Help), parse("help"));
+        assert_eq!(Ok(Command::Help), parse("solve --help"));
+    }
+}
@@ -0,0 +1,433 @@
+//! The bodies of the subcommands, printing their results and turning failures into exit codes.
+
+use std::{
+    fs,
+    io::{stdin, Read},
+    path::Path,
+    process::ExitCode,
+    time::{Duration, Instant},
+};
+
+use aoc2022::{
+    aoc_general::{AocRegistry, PuzzlePart},
+    bench::{self, BenchFormat},
+    cache::AnswerCache,
+    common,
+    fetch::{self, FetchOutcome},
+    http::AocWebsite,
+    report::{self, OutputFormat, Record},
+    run_all, scaffold,
+    submit::{self, SubmissionLog, SubmitOutcome, Submitted},
+    verify,
+    year_2022::day07::{
+        listing::{self, ListingFormat, SortOrder},
+        synthesis::{self, SynthesisOptions},
+        transcript::{self, Mode},
+    },
+};
+
+use crate::cli::{CacheAction, Day7Action, Day7Source, PartSelection};
+
+pub fn solve(
+    registry: &AocRegistry,
+    year: u16,
+    day: u8,
+    parts: PartSelection,
+    input_path: Option<&Path>,
+    format: OutputFormat,
+    cache: &AnswerCache,
+) -> ExitCode {
+    let input = match read_input(input_path) {
+        Some(i) => i,
+        None => return ExitCode::FAILURE,
+    };
+
+    let input_hash = common::input_hash(&input);
+    let solver = registry.year(year).and_then(|s| s.solver(day)).ok();
+
+    // the input is only parsed if one of the parts isn't cached
+    let mut parsed = None;
+
+    let results = parts
+        .parts()
+        .into_iter()
+        .map(|part| {
+            if let Some(answer) = solver.and_then(|s| cache.lookup(s, part, &input)) {
+                return (part, Ok(answer), Duration::ZERO, true);
+            }
+
+            let parsed = parsed.get_or_insert_with(|| registry.parse(year, day, &input));
+
+            let start = Instant::now();
+            let actual = match parsed {
+                Ok(p) => registry.solve_parsed(p, part),
+                Err(e) => Err(e.clone().in_puzzle(day, part)),
+            };
+            let elapsed = start.elapsed();
+
+            if let (Some(s), Ok(answer)) = (solver, &actual) {
+                cache.store(s, part, &input, answer);
+            }
+
+            (part, actual, elapsed, false)
+        })
+        .collect::<Vec<_>>();
+
+    // the solver reports the puzzle it actually parsed the input for
+    let (year, day) = match &parsed {
+        Some(Ok(p)) => (p.year(), p.day()),
+        _ => (year, day),
+    };
+
+    let records = results
+        .iter()
+        .map(|(part, actual, elapsed, cached)| Record {
+            year,
+            day,
+            part: *part,
+            actual,
+            expected: None,
+            status: None,
+            elapsed: *elapsed,
+            input_path,
+            input_hash: &input_hash,
+            cached: *cached,
+        })
+        .collect::<Vec<_>>();
+
+    // in text mode a parse error is only reported once instead of once per part
+    match (&parsed, format) {
+        (Some(Err(e)), OutputFormat::Text) => eprintln!("Error: {}", e),
+        (_, OutputFormat::Text) => {
+            for r in &records {
+                match r.actual {
+                    Ok(_) => println!("{}", report::to_text(r)),
+                    Err(_) => eprintln!("{}", report::to_text(r)),
+                }
+            }
+        }
+        _ => print!("{}", report::format_records(&records, format)),
+    }
+
+    if results.iter().any(|(_, actual, _, _)| actual.is_err()) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+pub fn bench(
+    registry: &AocRegistry,
+    year: u16,
+    data_dir: &Path,
+    runs: usize,
+    format: BenchFormat,
+) -> ExitCode {
+    let results = registry
+        .year(year)
+        .and_then(|solvers| bench::bench_data_dir(solvers, data_dir, runs));
+
+    match results {
+        Ok(r) => {
+            print!("{}", bench::format_results(&r, format));
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+pub fn run_all(
+    registry: &AocRegistry,
+    year: u16,
+    data_dir: &Path,
+    format: OutputFormat,
+    jobs: usize,
+    timeout: Option<Duration>,
+    cache: &AnswerCache,
+) -> ExitCode {
+    let solvers = match registry.year(year) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let summary = match run_all::run_all(solvers, data_dir, jobs, timeout, cache) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Failed to read '{}': {}", data_dir.display(), e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match format {
+        OutputFormat::Text => print!("{}", run_all::format_table(&summary)),
+        f => print!(
+            "{}",
+            report::format_records(
+                &summary.parts.iter().map(Record::from).collect::<Vec<_>>(),
+                f
+            )
+        ),
+    }
+
+    if summary.has_failures() {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+pub fn verify(
+    registry: &AocRegistry,
+    data_dir: &Path,
+    format: OutputFormat,
+    jobs: usize,
+    timeout: Option<Duration>,
+    cache: &AnswerCache,
+) -> ExitCode {
+    let results = match verify::verify_all_years(registry, data_dir, jobs, timeout, cache) {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("Failed to read '{}': {}", data_dir.display(), e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match format {
+        OutputFormat::Text => print!("{}", verify::format_table(&results)),
+        f => print!(
+            "{}",
+            report::format_records(&results.iter().map(Record::from).collect::<Vec<_>>(), f)
+        ),
+    }
+
+    if results.iter().any(|r| r.status.is_failure()) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+pub fn cache(registry: &AocRegistry, action: CacheAction, cache: &AnswerCache) -> ExitCode {
+    let result = match action {
+        CacheAction::Stats => cache.stats(registry).map(|stats| {
+            println!("cache: {}", cache.dir().display());
+            println!(
+                "{} answers, {} bytes, {} stale",
+                stats.entries, stats.bytes, stats.stale
+            );
+
+            for (year, entries) in stats.years {
+                println!("{}: {} answers", year, entries);
+            }
+        }),
+        CacheAction::Clear => cache
+            .clear()
+            .map(|removed| println!("removed {} cached answers", removed)),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Failed to access '{}': {}", cache.dir().display(), e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Reads a file or stdin, errors are reported and returned as `None`.
+fn read_input(input_path: Option<&Path>) -> Option<Vec<u8>> {
+    let input = match input_path {
+        Some(path) => fs::read(path),
+        None => {
+            let mut buffer = vec![];
+            stdin().lock().read_to_end(&mut buffer).map(|_| buffer)
+        }
+    };
+
+    match input {
+        Ok(i) => Some(i),
+        Err(e) => {
+            let source = input_path.map_or("stdin".into(), |p| p.display().to_string());
+            eprintln!("Failed to read '{}': {}", source, e);
+            None
+        }
+    }
+}
+
+pub fn day7(
+    action: Day7Action,
+    input_path: Option<&Path>,
+    sort: SortOrder,
+    format: ListingFormat,
+    mode: Mode,
+) -> ExitCode {
+    let input = match read_input(input_path) {
+        Some(i) => i,
+        None => return ExitCode::FAILURE,
+    };
+
+    // all issues are listed by check, even with --strict
+    let mode = match action {
+        Day7Action::Check => Mode::Lenient,
+        _ => mode,
+    };
+
+    let transcript = match transcript::parse(&input, mode) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match action {
+        Day7Action::Tree => print!("{}", listing::tree(&transcript.fs, sort, format)),
+        Day7Action::Du => print!("{}", listing::du(&transcript.fs, sort, format)),
+        Day7Action::Check => {
+            for issue in &transcript.issues {
+                println!("{}", issue);
+            }
+
+            if transcript.issues.is_empty() {
+                println!("no issues found");
+                return ExitCode::SUCCESS;
+            }
+
+            return ExitCode::FAILURE;
+        }
+    }
+
+    for issue in &transcript.issues {
+        eprintln!("Warning: {}", issue);
+    }
+
+    ExitCode::SUCCESS
+}
+
+pub fn day7_generate(source: &Day7Source, options: SynthesisOptions) -> ExitCode {
+    let fs = match source {
+        Day7Source::Transcript(input_path) => {
+            let input = match read_input(input_path.as_deref()) {
+                Some(i) => i,
+                None => return ExitCode::FAILURE,
+            };
+
+            match transcript::parse(&input, Mode::Strict) {
+                Ok(t) => t.fs,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        Day7Source::Directory(dir) => match synthesis::scan_dir(dir) {
+            Ok(fs) => fs,
+            Err(e) => {
+                eprintln!("Failed to scan '{}': {}", dir.display(), e);
+                return ExitCode::FAILURE;
+            }
+        },
+    };
+
+    print!("{}", synthesis::synthesize(&fs, options));
+    ExitCode::SUCCESS
+}
+
+pub fn new_day(year: u16, day: u8, src_dir: &Path) -> ExitCode {
+    match scaffold::add_day(src_dir, year, day) {
+        Ok(path) => {
+            println!("created {} and registered Day{}", path.display(), day);
+            println!(
+                "add data/{}/examples/{:02}-input.txt and {:02}-output.txt, then remove the #[ignore] of its test",
+                year, day, day
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+pub fn download_input(website: &AocWebsite, year: u16, day: u8, target: &Path) -> ExitCode {
+    match fetch::fetch_input(website, year, day, target) {
+        Ok(FetchOutcome::Downloaded(bytes)) => {
+            println!("downloaded {} bytes to {}", bytes, target.display());
+            ExitCode::SUCCESS
+        }
+        Ok(FetchOutcome::AlreadyOnDisk) => {
+            println!("{} is already on disk", target.display());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+pub fn submit(
+    registry: &AocRegistry,
+    website: &AocWebsite,
+    year: u16,
+    day: u8,
+    part: PuzzlePart,
+    user_dir: &Path,
+) -> ExitCode {
+    let input_path = user_dir.join(format!("{day:02}-input.txt"));
+
+    let input = match fs::read(&input_path) {
+        Ok(i) => i,
+        Err(e) => {
+            eprintln!("Failed to read '{}': {}", input_path.display(), e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let answer = match registry
+        .parse(year, day, &input)
+        .and_then(|parsed| registry.solve_parsed(&parsed, part))
+    {
+        Ok(a) => a,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!("{} day {} {}: {}", year, day, part, answer);
+
+    let output_path = user_dir.join(format!("{day:02}-output.txt"));
+
+    let result = SubmissionLog::open(user_dir.join(submit::LOG_FILE_NAME)).and_then(|mut log| {
+        submit::submit_answer(website, &mut log, year, day, part, &answer, &output_path)
+    });
+
+    match result {
+        Ok(Submitted::Refused(reason)) => {
+            eprintln!("Not submitted: {}", reason);
+            ExitCode::FAILURE
+        }
+        Ok(Submitted::Sent(response)) => {
+            println!("{}: {}", response.outcome, response.message);
+
+            if response.outcome == SubmitOutcome::Correct {
+                println!("saved to {}", output_path.display());
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
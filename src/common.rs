@@ -1,10 +1,25 @@
-use std::{iter::Peekable, str::FromStr};
+//! Input handling shared by the solvers and tools: line splitting, scanning, grids, ranges and data directories.
+
+use std::{
+    fs, io,
+    iter::Peekable,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use num::{
     traits::ops::overflowing::{OverflowingAdd, OverflowingSub},
     Integer,
 };
 
+use crate::aoc_general::SolveError;
+
+mod grid;
+mod scanner;
+
+pub use grid::{Direction, Grid};
+pub use scanner::Scanner;
+
 pub struct LineBreakNormalizer<I>
 where
     I: Iterator<Item = u8>,
@@ -87,7 +102,8 @@ where
 
 impl<I> LfEofDropable for I
 where
-    I: Iterator<Item = Vec<u8>>,
+    I: Iterator,
+    I::Item: AsRef<[u8]>,
 {
     fn drop_lf_eof(self) -> LfEofDropper<Self> {
         LfEofDropper {
@@ -97,29 +113,33 @@ where
 }
 
 pub trait LfEofDropable {
+    /// Works for owned lines (`Vec<u8>`) as well as borrowed ones (`&[u8]`).
     fn drop_lf_eof(self) -> LfEofDropper<Self>
     where
-        Self: Sized + Iterator<Item = Vec<u8>>;
+        Self: Sized + Iterator,
+        Self::Item: AsRef<[u8]>;
 }
 
 pub struct LfEofDropper<I>
 where
-    I: Iterator<Item = Vec<u8>>,
+    I: Iterator,
+    I::Item: AsRef<[u8]>,
 {
     iter: Peekable<I>,
 }
 
 impl<I> Iterator for LfEofDropper<I>
 where
-    I: Iterator<Item = Vec<u8>>,
+    I: Iterator,
+    I::Item: AsRef<[u8]>,
 {
-    type Item = Vec<u8>;
+    type Item = I::Item;
 
     fn next(&mut self) -> Option<Self::Item> {
         let item = self.iter.next();
 
         if let Some(x) = &item {
-            if x.is_empty() && self.iter.peek().is_none() {
+            if x.as_ref().is_empty() && self.iter.peek().is_none() {
                 // the current element is empty (zero length vector)
                 // and the last element so we drop it and directly return None.
                 return None;
@@ -130,6 +150,58 @@ where
     }
 }
 
+pub trait SliceLineSplittable {
+    /// Splits a buffer at LF, CRLF and CR line breaks without copying, so it's equivalent to
+    /// `normalize_line_breaks().split_lf_line_breaks()` but yields slices of the buffer.
+    fn split_line_breaks(&self) -> SliceLineSplitter<'_>;
+}
+
+impl SliceLineSplittable for [u8] {
+    fn split_line_breaks(&self) -> SliceLineSplitter<'_> {
+        SliceLineSplitter {
+            rest: self,
+            ended: false,
+        }
+    }
+}
+
+pub struct SliceLineSplitter<'a> {
+    rest: &'a [u8],
+    ended: bool,
+}
+
+impl<'a> Iterator for SliceLineSplitter<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.ended {
+            return None;
+        }
+
+        match self.rest.iter().position(|&x| x == b'\n' || x == b'\r') {
+            None => {
+                // like the LineSplitter the remainder after the last line break is a line,
+                // even if it's empty.
+                self.ended = true;
+                Some(self.rest)
+            }
+            Some(index) => {
+                let line = &self.rest[..index];
+
+                let line_break_length =
+                    if self.rest[index] == b'\r' && self.rest.get(index + 1) == Some(&b'\n') {
+                        2
+                    } else {
+                        1
+                    };
+
+                self.rest = &self.rest[index + line_break_length..];
+                Some(line)
+            }
+        }
+    }
+}
+
 pub struct RangeIteratorInclusive<T> {
     start: T,
     stop: T,
@@ -213,11 +285,67 @@ where
     }
 }
 
-pub fn parse<T: FromStr>(data: &[u8]) -> T {
-    match std::str::from_utf8(data).unwrap().parse::<T>() {
-        Err(_) => panic!("Failed to parse data"),
-        Ok(x) => x,
+/// Parses the bytes as UTF-8 text into `T`. The returned error carries no position,
+/// callers are expected to attach the line (and column) they know about.
+pub fn parse<T: FromStr>(data: &[u8]) -> Result<T, SolveError> {
+    std::str::from_utf8(data)
+        .ok()
+        .and_then(|x| x.parse::<T>().ok())
+        .ok_or_else(|| {
+            SolveError::new(format!(
+                "failed to parse '{}'",
+                String::from_utf8_lossy(data)
+            ))
+        })
+}
+
+/// A stable 64-bit FNV-1a hash of the input as 16 hex digits, used to tell inputs apart
+/// in reports. It's not meant to be cryptographically secure.
+pub fn input_hash(data: &[u8]) -> String {
+    let hash = data.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+
+    format!("{:016x}", hash)
+}
+
+/// A JSON string literal with quotes, backslashes and control characters escaped.
+pub fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
     }
+
+    escaped.push('"');
+    escaped
+}
+
+/// The paths of all entries of a directory, sorted so the output doesn't depend on the
+/// filesystem.
+pub fn sorted_entries(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|e| e.map(|e| e.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    entries.sort();
+    Ok(entries)
+}
+
+/// The last component of the path, empty if there is none.
+pub fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
 }
 
 pub struct LineSplitter<I>
@@ -261,7 +389,21 @@ mod tests {
     use crate::common::{LfEofDropable, NormalizeLineBreaks};
     use std::io::{Cursor, Read};
 
-    use super::{LineSplittable, RangeIteratorInclusive};
+    use super::{
+        input_hash, json_string, LineSplittable, RangeIteratorInclusive, SliceLineSplittable,
+    };
+
+    #[test]
+    fn input_hash_is_fnv1a_64() {
+        assert_eq!("cbf29ce484222325", input_hash(b""));
+        assert_eq!("af63dc4c8601ec8c", input_hash(b"a"));
+        assert_eq!("85944171f73967e8", input_hash(b"foobar"));
+    }
+
+    #[test]
+    fn json_strings_are_escaped() {
+        assert_eq!(r#""a\"b\\c\nd\u0001""#, json_string("a\"b\\c\nd\u{1}"));
+    }
 
     #[test]
     fn range_iterator_works_for_trivial_cases() {
@@ -505,4 +647,40 @@ mod tests {
 
         assert_eq!(expected, processed);
     }
+
+    #[test]
+    fn splitting_slices_matches_normalizing_and_splitting_bytes() {
+        let inputs: [&[u8]; 7] = [
+            b"some\rtest\r\r\nwith\ndifferent\n\nline\r\nbreak\nstyles",
+            b"test\r",
+            b"test\r\n",
+            b"\r\r\n\n",
+            b"no line break",
+            b"",
+            b"trailing\n\n",
+        ];
+
+        for input in inputs {
+            let expected: Vec<_> = input
+                .iter()
+                .copied()
+                .normalize_line_breaks()
+                .split_lf_line_breaks()
+                .collect();
+
+            let observed: Vec<_> = input.split_line_breaks().map(|x| x.to_vec()).collect();
+
+            assert_eq!(expected, observed);
+        }
+    }
+
+    #[test]
+    fn lf_eof_drop_works_for_slice_lines() {
+        let input = b"some\r\ntest\r\n\r\nbreaks\r\n";
+        let expected: [&[u8]; 4] = [b"some", b"test", b"", b"breaks"];
+
+        let processed: Vec<_> = input.split_line_breaks().drop_lf_eof().collect();
+
+        assert_eq!(expected.to_vec(), processed);
+    }
 }
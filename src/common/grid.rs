@@ -0,0 +1,380 @@
+use std::{fmt, ops::Index};
+
+use crate::aoc_general::SolveError;
+
+use super::{LfEofDropable, RangeIteratorInclusive, SliceLineSplittable};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl Direction {
+    /// The four directions that share an edge with a cell.
+    pub const ORTHOGONAL: [Direction; 4] = [
+        Direction::Left,
+        Direction::Right,
+        Direction::Up,
+        Direction::Down,
+    ];
+
+    /// All eight directions, including the diagonals that only share a corner.
+    pub const ALL: [Direction; 8] = [
+        Direction::Left,
+        Direction::Right,
+        Direction::Up,
+        Direction::Down,
+        Direction::UpLeft,
+        Direction::UpRight,
+        Direction::DownLeft,
+        Direction::DownRight,
+    ];
+
+    /// The change of `x` and `y` for a single step, `y` grows downwards.
+    pub const fn delta(self) -> (isize, isize) {
+        match self {
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::UpLeft => (-1, -1),
+            Direction::UpRight => (1, -1),
+            Direction::DownLeft => (-1, 1),
+            Direction::DownRight => (1, 1),
+        }
+    }
+}
+
+/// A rectangular grid of cells stored in row-major order. Positions are `(x, y)` with
+/// `(0, 0)` in the top left corner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn<F>(width: usize, height: usize, mut f: F) -> Self
+    where
+        F: FnMut(usize, usize) -> T,
+    {
+        let mut cells = Vec::with_capacity(width * height);
+
+        for y in 0..height {
+            for x in 0..width {
+                cells.push(f(x, y));
+            }
+        }
+
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// Parses a character map with one row per line. Each byte is converted with `map`,
+    /// errors of `map` and rows with a different width than the first one are reported with
+    /// their position. A single line break at the end of the input is ignored.
+    pub fn parse<F>(input: &[u8], mut map: F) -> Result<Self, SolveError>
+    where
+        F: FnMut(u8) -> Result<T, SolveError>,
+    {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+
+        for (y, line) in input.split_line_breaks().drop_lf_eof().enumerate() {
+            match width {
+                None => width = Some(line.len()),
+                Some(w) if w != line.len() => {
+                    return Err(SolveError::new(format!(
+                        "row should have the same width as the first row ({})",
+                        w
+                    ))
+                    .at_line(y + 1))
+                }
+                Some(_) => {}
+            }
+
+            for (x, &c) in line.iter().enumerate() {
+                cells.push(map(c).map_err(|e| e.at_line(y + 1).at_column(x + 1))?);
+            }
+
+            height += 1;
+        }
+
+        Ok(Self {
+            cells,
+            width: width.unwrap_or_default(),
+            height,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// All cells in row-major order, see [`Self::index_of`].
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn index_of(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.width && y < self.height).then(|| y * self.width + x)
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.index_of(x, y).map(|i| &self.cells[i])
+    }
+
+    /// The position one step away in the given direction, if it's still inside the grid.
+    pub fn step(&self, x: usize, y: usize, direction: Direction) -> Option<(usize, usize)> {
+        let (dx, dy) = direction.delta();
+
+        let x = x.checked_add_signed(dx)?;
+        let y = y.checked_add_signed(dy)?;
+
+        self.index_of(x, y).map(|_| (x, y))
+    }
+
+    /// Positions of the up to four cells sharing an edge with `(x, y)`.
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Direction::ORTHOGONAL
+            .into_iter()
+            .filter_map(move |d| self.step(x, y, d))
+    }
+
+    /// Positions of the up to eight cells sharing an edge or a corner with `(x, y)`.
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |d| self.step(x, y, d))
+    }
+
+    /// Cell indices from `(x, y)` (inclusive) to the edge of the grid in the given direction.
+    /// Returns `None` if the start is outside of the grid.
+    pub fn ray_indices(
+        &self,
+        x: usize,
+        y: usize,
+        direction: Direction,
+    ) -> Option<RangeIteratorInclusive<usize>> {
+        let start = self.index_of(x, y)?;
+        let (dx, dy) = direction.delta();
+
+        let available = |delta: isize, position: usize, size: usize| match delta {
+            -1 => position,
+            1 => size - 1 - position,
+            _ => usize::MAX,
+        };
+
+        let steps = available(dx, x, self.width).min(available(dy, y, self.height));
+
+        if steps == 0 {
+            return RangeIteratorInclusive::new(start, start, 1);
+        }
+
+        // the step is negative for all directions that walk to the top left
+        let step = dy * self.width as isize + dx;
+        let distance = steps * step.unsigned_abs();
+
+        let stop = if step < 0 {
+            start - distance
+        } else {
+            start + distance
+        };
+
+        RangeIteratorInclusive::new(start, stop, step.unsigned_abs())
+    }
+
+    /// Positions from `(x, y)` (inclusive) to the edge of the grid in the given direction.
+    /// Empty if the start is outside of the grid.
+    pub fn ray(
+        &self,
+        x: usize,
+        y: usize,
+        direction: Direction,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.ray_indices(x, y, direction)
+            .into_iter()
+            .flatten()
+            .map(|i| (i % self.width, i / self.width))
+    }
+
+    /// Like [`Self::ray`] but yields the cells instead of their positions.
+    pub fn ray_values(
+        &self,
+        x: usize,
+        y: usize,
+        direction: Direction,
+    ) -> impl Iterator<Item = &T> + '_ {
+        self.ray_indices(x, y, direction)
+            .into_iter()
+            .flatten()
+            .map(|i| &self.cells[i])
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        (y < self.height).then(|| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        (0..self.height).filter_map(|y| self.row(y))
+    }
+
+    /// The cells of column `x` from top to bottom, empty if `x` is outside of the grid.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> + '_ {
+        let height = if x < self.width { self.height } else { 0 };
+        (0..height).map(move |y| &self.cells[y * self.width + x])
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T> + '_> + '_ {
+        (0..self.width).map(|x| self.column(x))
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Mirrors the grid along the diagonal from the top left to the bottom right.
+    pub fn transpose(&self) -> Self {
+        Grid::from_fn(self.height, self.width, |x, y| self[(y, x)].clone())
+    }
+
+    pub fn rotate_clockwise(&self) -> Self {
+        Grid::from_fn(self.height, self.width, |x, y| {
+            self[(y, self.height - 1 - x)].clone()
+        })
+    }
+
+    pub fn rotate_counter_clockwise(&self) -> Self {
+        Grid::from_fn(self.height, self.width, |x, y| {
+            self[(self.width - 1 - y, x)].clone()
+        })
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    /// Panics if the position is outside of the grid, use [`Grid::get`] to check the bounds.
+    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+        self.get(x, y)
+            .expect("position should be inside of the grid")
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    /// Renders each row on its own line without separators between the cells.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Direction, Grid};
+    use crate::aoc_general::SolveError;
+
+    fn chars(input: &[u8]) -> Grid<char> {
+        Grid::parse(input, |c| Ok(c as char)).unwrap()
+    }
+
+    #[test]
+    fn parsing_reports_ragged_rows_and_mapping_errors_with_position() {
+        let ragged = Grid::parse(b"123\r\n45\r\n", Ok);
+        assert_eq!(Some(2), ragged.unwrap_err().line);
+
+        let invalid = Grid::parse(b"12\n3x\n", |c| {
+            c.is_ascii_digit()
+                .then_some(c - b'0')
+                .ok_or_else(|| SolveError::new("not a digit"))
+        })
+        .unwrap_err();
+
+        assert_eq!((Some(2), Some(2)), (invalid.line, invalid.column));
+    }
+
+    #[test]
+    fn access_is_bounds_checked() {
+        let grid = chars(b"ab\ncd\nef");
+
+        assert_eq!((2, 3), (grid.width(), grid.height()));
+        assert_eq!(Some(&'d'), grid.get(1, 1));
+        assert_eq!('e', grid[(0, 2)]);
+        assert_eq!(None, grid.get(2, 0));
+        assert_eq!(None, grid.get(0, 3));
+    }
+
+    #[test]
+    fn neighbours_stay_inside_of_the_grid() {
+        let grid = chars(b"abc\ndef\nghi");
+
+        let mut corner = grid.neighbours4(0, 0).collect::<Vec<_>>();
+        corner.sort();
+        assert_eq!(vec![(0, 1), (1, 0)], corner);
+
+        assert_eq!(4, grid.neighbours4(1, 1).count());
+        assert_eq!(3, grid.neighbours8(2, 2).count());
+        assert_eq!(8, grid.neighbours8(1, 1).count());
+    }
+
+    #[test]
+    fn rays_walk_to_the_edge_in_every_direction() {
+        let grid = chars(b"abcd\nefgh\nijkl");
+        let ray = |x, y, d| grid.ray_values(x, y, d).collect::<String>();
+
+        assert_eq!("fgh", ray(1, 1, Direction::Right));
+        assert_eq!("fe", ray(1, 1, Direction::Left));
+        assert_eq!("fb", ray(1, 1, Direction::Up));
+        assert_eq!("fj", ray(1, 1, Direction::Down));
+        assert_eq!("fa", ray(1, 1, Direction::UpLeft));
+        assert_eq!("fc", ray(1, 1, Direction::UpRight));
+        assert_eq!("fi", ray(1, 1, Direction::DownLeft));
+        assert_eq!("fk", ray(1, 1, Direction::DownRight));
+        assert_eq!("l", ray(3, 2, Direction::DownRight));
+        assert_eq!("", ray(4, 0, Direction::Left));
+    }
+
+    #[test]
+    fn rows_and_columns_can_be_iterated() {
+        let grid = chars(b"ab\ncd");
+
+        let rows = grid.rows().map(|r| r.iter().collect::<String>());
+        assert_eq!(vec!["ab", "cd"], rows.collect::<Vec<_>>());
+
+        let columns = grid.columns().map(|c| c.collect::<String>());
+        assert_eq!(vec!["ac", "bd"], columns.collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn grids_can_be_transposed_and_rotated() {
+        let grid = chars(b"abc\ndef");
+
+        assert_eq!("ad\nbe\ncf", grid.transpose().to_string());
+        assert_eq!("da\neb\nfc", grid.rotate_clockwise().to_string());
+        assert_eq!("cf\nbe\nad", grid.rotate_counter_clockwise().to_string());
+        assert_eq!(grid, grid.rotate_clockwise().rotate_counter_clockwise());
+    }
+}
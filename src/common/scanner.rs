@@ -0,0 +1,332 @@
+use std::str::FromStr;
+
+use num::Integer;
+
+use crate::aoc_general::SolveError;
+
+/// Reads tokens from byte input while keeping track of the (1-based) line and column,
+/// so errors point at the place where the input didn't match.
+///
+/// LF, CRLF and single CR are all treated as a line break.
+pub struct Scanner<'a> {
+    rest: &'a [u8],
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(input: &'a [u8]) -> Self {
+        Self::for_line(input, 1)
+    }
+
+    /// Scans a single line that was already split from the input, errors report `line_number`.
+    pub fn for_line(line: &'a [u8], line_number: usize) -> Self {
+        Self {
+            rest: line,
+            line: line_number,
+            column: 1,
+        }
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// The current `(line, column)`, can be used with [`Self::error_at`] later on.
+    pub fn position(&self) -> (usize, usize) {
+        (self.line, self.column)
+    }
+
+    pub fn is_eof(&self) -> bool {
+        self.rest.is_empty()
+    }
+
+    pub fn is_line_end(&self) -> bool {
+        matches!(self.peek(), None | Some(b'\n' | b'\r'))
+    }
+
+    pub fn peek(&self) -> Option<u8> {
+        self.rest.first().copied()
+    }
+
+    /// The input that wasn't consumed yet.
+    pub fn rest(&self) -> &'a [u8] {
+        self.rest
+    }
+
+    /// An error at the current position.
+    pub fn error(&self, reason: impl Into<String>) -> SolveError {
+        self.error_at(self.position(), reason)
+    }
+
+    pub fn error_at(
+        &self,
+        (line, column): (usize, usize),
+        reason: impl Into<String>,
+    ) -> SolveError {
+        SolveError::new(reason).at_line(line).at_column(column)
+    }
+
+    pub fn next_byte(&mut self) -> Option<u8> {
+        let (&c, rest) = self.rest.split_first()?;
+        self.rest = rest;
+
+        match c {
+            // the LF of a CRLF line break finishes the line
+            b'\r' if self.peek() == Some(b'\n') => {}
+            b'\n' | b'\r' => {
+                self.line += 1;
+                self.column = 1;
+            }
+            _ => self.column += 1,
+        }
+
+        Some(c)
+    }
+
+    fn advance(&mut self, count: usize) -> &'a [u8] {
+        let consumed = &self.rest[..count];
+
+        for _ in 0..count {
+            self.next_byte();
+        }
+
+        consumed
+    }
+
+    pub fn starts_with(&self, literal: &[u8]) -> bool {
+        self.rest.starts_with(literal)
+    }
+
+    /// Consumes `literal` if the input continues with it.
+    pub fn try_literal(&mut self, literal: &[u8]) -> bool {
+        if self.starts_with(literal) {
+            self.advance(literal.len());
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn expect_literal(&mut self, literal: &[u8]) -> Result<(), SolveError> {
+        if self.try_literal(literal) {
+            return Ok(());
+        }
+
+        Err(self.error(format!(
+            "expected '{}', found {}",
+            String::from_utf8_lossy(literal),
+            self.describe_next(literal.len())
+        )))
+    }
+
+    /// Consumes a line break, or nothing at the end of the input.
+    pub fn expect_line_end(&mut self) -> Result<(), SolveError> {
+        match self.peek() {
+            None => Ok(()),
+            Some(b'\n' | b'\r') => {
+                self.skip_line_break();
+                Ok(())
+            }
+            Some(_) => Err(self.error(format!(
+                "expected the end of the line, found {}",
+                self.describe_next(self.line_length())
+            ))),
+        }
+    }
+
+    /// Consumes bytes as long as `predicate` holds.
+    pub fn read_while<P>(&mut self, mut predicate: P) -> &'a [u8]
+    where
+        P: FnMut(u8) -> bool,
+    {
+        let count = self
+            .rest
+            .iter()
+            .position(|&c| !predicate(c))
+            .unwrap_or(self.rest.len());
+
+        self.advance(count)
+    }
+
+    /// Consumes everything before the next `delimiter` (or the end of the input).
+    /// The delimiter itself isn't consumed.
+    pub fn read_until(&mut self, delimiter: u8) -> &'a [u8] {
+        self.read_while(|c| c != delimiter)
+    }
+
+    /// The rest of the current line without consuming it.
+    pub fn peek_line(&self) -> &'a [u8] {
+        &self.rest[..self.line_length()]
+    }
+
+    /// Consumes the rest of the current line including its line break, which isn't returned.
+    pub fn read_line(&mut self) -> &'a [u8] {
+        let line = self.advance(self.line_length());
+        self.skip_line_break();
+        line
+    }
+
+    /// Skips spaces, tabs and line breaks.
+    pub fn skip_whitespace(&mut self) {
+        self.read_while(|c| c.is_ascii_whitespace());
+    }
+
+    /// Reads a number without a sign.
+    pub fn read_uint<T>(&mut self) -> Result<T, SolveError>
+    where
+        T: Integer + FromStr,
+    {
+        let start = self.position();
+        let digits = self.read_while(|c| c.is_ascii_digit());
+
+        self.parse_number(digits, start)
+    }
+
+    /// Reads a number with an optional `+` or `-` sign.
+    pub fn read_int<T>(&mut self) -> Result<T, SolveError>
+    where
+        T: Integer + FromStr,
+    {
+        let start = self.position();
+        let sign = match self.peek() {
+            Some(b'+' | b'-') => 1,
+            _ => 0,
+        };
+
+        let digit_count = self.rest[sign..]
+            .iter()
+            .take_while(|c| c.is_ascii_digit())
+            .count();
+
+        if digit_count == 0 {
+            return Err(self.error(format!(
+                "expected a number, found {}",
+                self.describe_next(1 + sign)
+            )));
+        }
+
+        let number = self.advance(sign + digit_count);
+        self.parse_number(number, start)
+    }
+
+    fn parse_number<T: FromStr>(
+        &self,
+        number: &[u8],
+        start: (usize, usize),
+    ) -> Result<T, SolveError> {
+        if number.is_empty() {
+            return Err(self.error(format!(
+                "expected a number, found {}",
+                self.describe_next(1)
+            )));
+        }
+
+        // only ASCII digits and signs, so this is valid UTF-8
+        std::str::from_utf8(number)
+            .ok()
+            .and_then(|n| n.parse::<T>().ok())
+            .ok_or_else(|| {
+                self.error_at(
+                    start,
+                    format!("number {} is out of range", String::from_utf8_lossy(number)),
+                )
+            })
+    }
+
+    fn line_length(&self) -> usize {
+        self.rest
+            .iter()
+            .position(|&c| c == b'\n' || c == b'\r')
+            .unwrap_or(self.rest.len())
+    }
+
+    fn skip_line_break(&mut self) {
+        if !self.try_literal(b"\r\n") && !self.try_literal(b"\n") {
+            self.try_literal(b"\r");
+        }
+    }
+
+    /// Describes up to `count` of the next bytes on the current line for error messages.
+    fn describe_next(&self, count: usize) -> String {
+        if self.is_eof() {
+            return "the end of the input".to_string();
+        }
+
+        let next = &self.rest[..count.max(1).min(self.line_length())];
+
+        if next.is_empty() {
+            "the end of the line".to_string()
+        } else {
+            format!("'{}'", String::from_utf8_lossy(next))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Scanner;
+
+    #[test]
+    fn lines_and_columns_are_tracked_across_all_line_breaks() {
+        let mut scanner = Scanner::new(b"ab\ncd\r\nef\rg");
+
+        assert_eq!(b"ab", scanner.read_line());
+        assert_eq!((2, 1), scanner.position());
+
+        scanner.next_byte();
+        assert_eq!((2, 2), scanner.position());
+
+        assert_eq!(b"d", scanner.read_line());
+        assert_eq!(b"ef", scanner.read_line());
+        assert_eq!((4, 1), scanner.position());
+        assert_eq!(b"g", scanner.read_line());
+        assert!(scanner.is_eof());
+    }
+
+    #[test]
+    fn numbers_and_literals_can_be_read() {
+        let mut scanner = Scanner::new(b"move 13 from -2 to +7");
+
+        scanner.expect_literal(b"move ").unwrap();
+        assert_eq!(13u32, scanner.read_uint().unwrap());
+        assert!(scanner.try_literal(b" from "));
+        assert_eq!(-2i64, scanner.read_int().unwrap());
+        assert!(!scanner.try_literal(b" from "));
+        assert_eq!(b" to ", scanner.read_until(b'+'));
+        assert_eq!(7i8, scanner.read_int().unwrap());
+        scanner.expect_line_end().unwrap();
+    }
+
+    #[test]
+    fn errors_point_at_the_unexpected_input() {
+        let mut scanner = Scanner::for_line(b"12-x", 5);
+
+        assert_eq!(12u8, scanner.read_uint().unwrap());
+        let error = scanner.expect_literal(b",").unwrap_err();
+        assert_eq!((Some(5), Some(3)), (error.line, error.column));
+        assert_eq!("expected ',', found '-'", error.reason);
+
+        scanner.skip_whitespace();
+        assert!(scanner.try_literal(b"-"));
+        let error = scanner.read_uint::<u32>().unwrap_err();
+        assert_eq!(Some(4), error.column);
+
+        let error = Scanner::new(b"  300").read_uint::<u8>().unwrap_err();
+        assert_eq!("expected a number, found ' '", error.reason);
+
+        let mut scanner = Scanner::new(b"300");
+        let error = scanner.read_uint::<u8>().unwrap_err();
+        assert_eq!((Some(1), Some(1)), (error.line, error.column));
+        assert!(scanner.is_eof());
+
+        let mut scanner = Scanner::new(b"1 2");
+        assert_eq!(1u32, scanner.read_uint().unwrap());
+        let error = scanner.expect_line_end().unwrap_err();
+        assert_eq!("expected the end of the line, found ' 2'", error.reason);
+    }
+}
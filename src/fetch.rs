@@ -0,0 +1,132 @@
+//! Downloading puzzle inputs into the data directory.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::http::AocWebsite;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FetchOutcome {
+    /// The input was downloaded and stored, with its size in bytes.
+    Downloaded(usize),
+    /// The input was already on disk, nothing was requested.
+    AlreadyOnDisk,
+}
+
+/// `<data_root>/<year>/<user>`, where `verify` expects the inputs and outputs of a user.
+pub fn user_dir(data_root: &Path, year: u16, user: &str) -> PathBuf {
+    data_root.join(year.to_string()).join(user)
+}
+
+/// `NN-input.txt` in the [`user_dir`].
+pub fn input_path(data_root: &Path, year: u16, user: &str, day: u8) -> PathBuf {
+    user_dir(data_root, year, user).join(format!("{day:02}-input.txt"))
+}
+
+/// Downloads the input of a puzzle to `target` unless it already exists. Inputs never
+/// change, so an existing file is never requested again.
+pub fn fetch_input(
+    website: &AocWebsite,
+    year: u16,
+    day: u8,
+    target: &Path,
+) -> io::Result<FetchOutcome> {
+    if target.exists() {
+        return Ok(FetchOutcome::AlreadyOnDisk);
+    }
+
+    let input = website.get(&format!("/{}/day/{}/input", year, day))?;
+
+    if input.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "the downloaded input is empty",
+        ));
+    }
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // a partially written file would be taken for a complete input by the next run
+    let temp_path = target.with_extension("txt.part");
+    fs::write(&temp_path, &input)?;
+    fs::rename(&temp_path, target)?;
+
+    Ok(FetchOutcome::Downloaded(input.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::Path, process, time::Duration};
+
+    use super::{fetch_input, input_path, FetchOutcome};
+    use crate::{
+        http::{AocWebsite, RateLimiter, Session},
+        test_support::{fake_server, ok},
+    };
+
+    fn website(base_url: &str, dir: &Path) -> AocWebsite {
+        AocWebsite::new(
+            base_url,
+            Session::Token("abc123".into()),
+            RateLimiter::new(dir.join("last-request"), Duration::ZERO),
+        )
+    }
+
+    #[test]
+    fn inputs_are_downloaded_once() {
+        let dir = env::temp_dir().join(format!("aoc-fetch-{}", process::id()));
+        _ = fs::remove_dir_all(&dir);
+
+        let (base_url, server) = fake_server(vec![ok("1000\n2000\n")]);
+        let website = website(&base_url, &dir);
+
+        let target = input_path(&dir, 2022, "someone", 1);
+        assert_eq!(
+            Path::new("2022/someone/01-input.txt"),
+            target.strip_prefix(&dir).unwrap()
+        );
+
+        assert_eq!(
+            FetchOutcome::Downloaded(10),
+            fetch_input(&website, 2022, 1, &target).unwrap()
+        );
+
+        let request = &server.join().unwrap()[0];
+        assert!(request.starts_with("GET /2022/day/1/input HTTP/1.1\r\n"));
+        assert!(request.contains("\r\ncookie: session=abc123\r\n"));
+        assert_eq!("1000\n2000\n", fs::read_to_string(&target).unwrap());
+
+        // the stand-in server is gone, a second request would fail
+        assert_eq!(
+            FetchOutcome::AlreadyOnDisk,
+            fetch_input(&website, 2022, 1, &target).unwrap()
+        );
+
+        _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn error_responses_are_reported() {
+        let dir = env::temp_dir().join(format!("aoc-fetch-error-{}", process::id()));
+        _ = fs::remove_dir_all(&dir);
+
+        let (base_url, server) = fake_server(vec![
+            "HTTP/1.1 400 Bad Request\r\nConnection: close\r\n\r\nPuzzle inputs differ by user.  Please log in to get your puzzle input.\n".into(),
+        ]);
+
+        let target = dir.join("01-input.txt");
+        let error = fetch_input(&website(&base_url, &dir), 2022, 1, &target).unwrap_err();
+        server.join().unwrap();
+
+        assert!(error
+            .to_string()
+            .contains("answered 400 (is the session token still valid?)"));
+        assert!(!target.exists());
+
+        _ = fs::remove_dir_all(&dir);
+    }
+}
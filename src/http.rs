@@ -0,0 +1,299 @@
+//! Requests to the Advent of Code website, authenticated with the session token and rate-limited.
+
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use ureq::Agent;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Environment variable with the session token, it takes precedence over the session file.
+pub const SESSION_ENV: &str = "AOC_SESSION";
+
+/// Default minimum time between two requests, also between separate runs of the tool.
+pub const REQUEST_INTERVAL: Duration = Duration::from_secs(5);
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Limit for a whole request, from connecting to reading the last byte of the response.
+const TRANSFER_TIMEOUT: Duration = Duration::from_secs(30);
+
+const USER_AGENT: &str = concat!(
+    "aoc2022/",
+    env!("CARGO_PKG_VERSION"),
+    " (command line tool)"
+);
+
+/// `$HOME/.config/aoc2022/session`, `None` if there is no home directory.
+pub fn default_session_file() -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| Path::new(&home).join(".config/aoc2022/session"))
+}
+
+/// Reads the session token from [`SESSION_ENV`] or the first line of `session_file`.
+pub fn read_session(session_file: Option<&Path>) -> io::Result<String> {
+    if let Some(session) = env::var(SESSION_ENV).ok().filter(|s| !s.trim().is_empty()) {
+        return check_session(session.trim(), SESSION_ENV);
+    }
+
+    let missing = || {
+        let file = session_file.map_or("a session file".into(), |f| f.display().to_string());
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "no session token, set {} or write it to {}",
+                SESSION_ENV, file
+            ),
+        )
+    };
+
+    let content = match session_file.map(fs::read_to_string) {
+        None => return Err(missing()),
+        Some(Err(e)) if e.kind() == io::ErrorKind::NotFound => return Err(missing()),
+        Some(r) => r?,
+    };
+
+    match (content.lines().next().map(str::trim), session_file) {
+        (Some(session), Some(file)) if !session.is_empty() => {
+            check_session(session, &file.display().to_string())
+        }
+        _ => Err(missing()),
+    }
+}
+
+/// The session cookie of the website is hex, anything else would end up unescaped in the
+/// `Cookie` header.
+fn check_session(session: &str, source: &str) -> io::Result<String> {
+    if session.is_empty()
+        || !session
+            .bytes()
+            .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+    {
+        return Err(invalid_data(format!(
+            "the session token in {} should only contain the hex digits 0-9 and a-f",
+            source
+        )));
+    }
+
+    Ok(session.to_string())
+}
+
+/// Enforces a minimum interval between requests, like [`REQUEST_INTERVAL`]. The time of the
+/// last request is kept in a file, so it also applies to separate runs.
+pub struct RateLimiter {
+    state_file: PathBuf,
+    interval: Duration,
+}
+
+impl RateLimiter {
+    pub fn new(state_file: impl Into<PathBuf>, interval: Duration) -> Self {
+        RateLimiter {
+            state_file: state_file.into(),
+            interval,
+        }
+    }
+
+    /// Sleeps until the interval since the last request has passed and records a new one.
+    pub fn wait(&self) -> io::Result<()> {
+        let last_request = fs::read_to_string(&self.state_file)
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok())
+            .map(Duration::from_nanos);
+
+        if let Some(last_request) = last_request {
+            let next_allowed = last_request + self.interval;
+            let now = unix_time();
+
+            if next_allowed > now {
+                thread::sleep(next_allowed - now);
+            }
+        }
+
+        if let Some(parent) = self.state_file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(&self.state_file, unix_time().as_nanos().to_string())
+    }
+}
+
+fn unix_time() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+pub enum Session {
+    /// Looked up with [`read_session`] when the first request is made, so commands that
+    /// don't need to make a request also work without a token.
+    Lookup(Option<PathBuf>),
+    Token(String),
+}
+
+/// The website with everything needed to make requests to it.
+pub struct AocWebsite {
+    agent: Agent,
+    base_url: String,
+    session: Session,
+    rate_limiter: RateLimiter,
+}
+
+impl AocWebsite {
+    pub fn new(base_url: &str, session: Session, rate_limiter: RateLimiter) -> Self {
+        let agent = Agent::config_builder()
+            .user_agent(USER_AGENT)
+            .timeout_connect(Some(CONNECT_TIMEOUT))
+            .timeout_global(Some(TRANSFER_TIMEOUT))
+            .http_status_as_error(false)
+            .build()
+            .into();
+
+        AocWebsite {
+            agent,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session,
+            rate_limiter,
+        }
+    }
+
+    /// Requests `path` (like `/2022/day/1/input`) and returns the body of a `200` response.
+    pub fn get(&self, path: &str) -> io::Result<Vec<u8>> {
+        self.send(path, None)
+    }
+
+    /// Posts `form` to `path` and returns the body of a `200` response.
+    pub fn post_form(&self, path: &str, form: String) -> io::Result<Vec<u8>> {
+        self.send(path, Some(form))
+    }
+
+    fn send(&self, path: &str, form: Option<String>) -> io::Result<Vec<u8>> {
+        let url = format!("{}{}", self.base_url, path);
+        let cookie = match &self.session {
+            Session::Lookup(file) => format!("session={}", read_session(file.as_deref())?),
+            Session::Token(token) => format!("session={}", check_session(token, "the request")?),
+        };
+
+        self.rate_limiter.wait()?;
+
+        let response = match form {
+            None => self.agent.get(&url).header("Cookie", &cookie).call(),
+            Some(form) => self
+                .agent
+                .post(&url)
+                .header("Cookie", &cookie)
+                .content_type("application/x-www-form-urlencoded")
+                .send(form),
+        };
+
+        let mut response = response.map_err(ureq::Error::into_io)?;
+        let status = response.status().as_u16();
+        let body = response
+            .body_mut()
+            .read_to_vec()
+            .map_err(ureq::Error::into_io)?;
+
+        if status == 200 {
+            return Ok(body);
+        }
+
+        let body = String::from_utf8_lossy(&body);
+        let message = body.lines().next().unwrap_or_default().trim();
+
+        let hint = match status {
+            400 | 500 if message.contains("log in") => " (is the session token still valid?)",
+            404 => " (is the puzzle unlocked yet?)",
+            _ => "",
+        };
+
+        Err(io::Error::other(format!(
+            "{} answered {}{}: {}",
+            url, status, hint, message
+        )))
+    }
+}
+
+/// Percent-encodes `value` for a form body.
+pub fn form_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            b' ' => "+".into(),
+            b => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env, fs, process,
+        time::{Duration, Instant},
+    };
+
+    use super::{check_session, form_encode, AocWebsite, RateLimiter, Session};
+    use crate::test_support::{fake_server_on, ok};
+
+    #[test]
+    fn form_values_are_percent_encoded() {
+        assert_eq!("-12+a%26b%3D%C3%A4", form_encode("-12 a&b=ä"));
+    }
+
+    #[test]
+    fn sessions_are_hex_digits() {
+        assert_eq!(
+            "53616c7465645f5f",
+            check_session("53616c7465645f5f", "x").unwrap()
+        );
+
+        for session in ["", "abc\r\nX-Injected: 1", "ABC", "stand-in-token"] {
+            assert!(check_session(session, "x").is_err(), "{:?}", session);
+        }
+    }
+
+    #[test]
+    fn ipv6_hosts_are_requested() {
+        let state_file = env::temp_dir().join(format!("aoc-ipv6-{}", process::id()));
+        let (base_url, server) = fake_server_on("[::1]:0", vec![ok("1000\n")]);
+
+        let website = AocWebsite::new(
+            &base_url,
+            Session::Token("abc123".into()),
+            RateLimiter::new(&state_file, Duration::ZERO),
+        );
+
+        let body = website.get("/2022/day/1/input");
+        let request = &server.join().unwrap()[0];
+        _ = fs::remove_file(&state_file);
+
+        assert_eq!(b"1000\n", &body.unwrap()[..]);
+        assert!(request.starts_with("GET /2022/day/1/input HTTP/1.1\r\n"));
+        assert!(request.contains(&format!("\r\nhost: {}\r\n", &base_url[7..])));
+    }
+
+    #[test]
+    fn requests_are_spaced_by_the_interval() {
+        let state_file = env::temp_dir().join(format!("aoc-rate-limit-{}", process::id()));
+        _ = fs::remove_file(&state_file);
+
+        let limiter = RateLimiter::new(&state_file, Duration::from_millis(100));
+        let start = Instant::now();
+
+        limiter.wait().unwrap();
+        assert!(start.elapsed() < Duration::from_millis(100));
+
+        limiter.wait().unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        _ = fs::remove_file(&state_file);
+    }
+}
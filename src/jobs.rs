@@ -0,0 +1,388 @@
+//! Running puzzle parts on a worker pool, isolated from panics and with optional timeouts.
+
+use std::{
+    any::Any,
+    cell::Cell,
+    iter,
+    num::NonZeroUsize,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, RecvTimeoutError},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::aoc_general::{Answer, DaySolver, PuzzlePart, SolveError};
+
+/// Number of worker threads if `--jobs` isn't given.
+pub fn default_jobs() -> usize {
+    thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+/// Parsing a single input once and solving the requested parts with the same model.
+#[derive(Clone)]
+pub struct InputJob {
+    pub solver: DaySolver,
+    pub parts: Vec<PuzzlePart>,
+    pub input: Arc<[u8]>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Termination {
+    Completed,
+    Panicked,
+    TimedOut,
+}
+
+pub struct InputOutcome {
+    /// Zero if the input didn't have to be parsed.
+    pub parse_elapsed: Duration,
+    /// One outcome per part of the job, in the same order.
+    pub parts: Vec<PartOutcome>,
+}
+
+pub struct PartOutcome {
+    pub actual: Result<Answer, SolveError>,
+    pub termination: Termination,
+    /// Time spent in the part itself.
+    pub elapsed: Duration,
+    /// The answer was taken from the [`crate::cache::AnswerCache`] without solving anything.
+    pub cached: bool,
+}
+
+/// Progress of a job, reported by [`InputJob::run_isolated`] as soon as it happens.
+enum Step {
+    Parsed(Duration),
+    Solved(PartOutcome),
+}
+
+/// Why a job on a watchdog thread stopped before all of its parts were solved.
+struct Interruption {
+    reason: String,
+    termination: Termination,
+    elapsed: Duration,
+}
+
+impl InputJob {
+    /// Runs the job on a watchdog thread if there is a timeout, which applies to parsing
+    /// and to every part on its own. Once a step doesn't finish in time the parts that
+    /// weren't solved yet are reported as error, the thread keeps running in the background
+    /// until the process exits because threads can't be cancelled.
+    pub fn run(&self, timeout: Option<Duration>) -> InputOutcome {
+        let timeout = match timeout {
+            None => {
+                let mut steps = vec![];
+                self.run_isolated(|step| steps.push(Ok(step)));
+                return self.collect(steps);
+            }
+            Some(t) => t,
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        let job = self.clone();
+
+        let spawned = thread::Builder::new()
+            .name(format!("day {}", self.solver.day()))
+            .spawn(move || {
+                // the receiver is gone if the job timed out, nobody is interested anymore
+                job.run_isolated(|step| _ = sender.send(step));
+            });
+
+        if let Err(e) = spawned {
+            return self.collect([Err(Interruption {
+                reason: format!("failed to start the solver thread: {}", e),
+                termination: Termination::Panicked,
+                elapsed: Duration::ZERO,
+            })]);
+        }
+
+        self.collect(iter::from_fn(|| {
+            Some(match receiver.recv_timeout(timeout) {
+                Ok(step) => Ok(step),
+                Err(RecvTimeoutError::Timeout) => Err(Interruption {
+                    reason: format!("timed out after {:?}", timeout),
+                    termination: Termination::TimedOut,
+                    elapsed: timeout,
+                }),
+                Err(RecvTimeoutError::Disconnected) => Err(Interruption {
+                    reason: "solver thread ended without a result".into(),
+                    termination: Termination::Panicked,
+                    elapsed: Duration::ZERO,
+                }),
+            })
+        }))
+    }
+
+    /// Parses the input and solves the parts one after the other. A panicking solver is
+    /// reported as an error instead of taking down the other jobs.
+    fn run_isolated(&self, mut report: impl FnMut(Step)) {
+        let start = Instant::now();
+        let parsed = catch_solver_panic(|| self.solver.parse(&self.input));
+        report(Step::Parsed(start.elapsed()));
+
+        let parsed = match parsed {
+            Ok(p) => p,
+            Err(payload) => {
+                let reason = format!("solver panicked: {}", panic_message(&payload));
+
+                for &part in &self.parts {
+                    let failed = self.failed(part, reason.clone(), Termination::Panicked);
+                    report(Step::Solved(failed));
+                }
+
+                return;
+            }
+        };
+
+        for &part in &self.parts {
+            let start = Instant::now();
+            let actual = match &parsed {
+                Ok(p) => catch_solver_panic(|| self.solver.solve_parsed(p, part)),
+                Err(e) => Ok(Err(e.clone().in_puzzle(self.solver.day(), part))),
+            };
+            let elapsed = start.elapsed();
+
+            report(Step::Solved(match actual {
+                Ok(actual) => PartOutcome {
+                    actual,
+                    termination: Termination::Completed,
+                    elapsed,
+                    cached: false,
+                },
+                Err(payload) => PartOutcome {
+                    elapsed,
+                    ..self.failed(
+                        part,
+                        format!("solver panicked: {}", panic_message(&payload)),
+                        Termination::Panicked,
+                    )
+                },
+            }));
+        }
+    }
+
+    /// Collects the steps until every part is solved. The parts that aren't solved when
+    /// the steps are interrupted fail with the interruption.
+    fn collect(&self, steps: impl IntoIterator<Item = Result<Step, Interruption>>) -> InputOutcome {
+        let mut outcome = InputOutcome {
+            parse_elapsed: Duration::ZERO,
+            parts: vec![],
+        };
+
+        let mut steps = steps.into_iter();
+
+        while outcome.parts.len() < self.parts.len() {
+            match steps.next() {
+                Some(Ok(Step::Parsed(elapsed))) => outcome.parse_elapsed = elapsed,
+                Some(Ok(Step::Solved(part))) => outcome.parts.push(part),
+                Some(Err(interruption)) => {
+                    for &part in &self.parts[outcome.parts.len()..] {
+                        outcome.parts.push(PartOutcome {
+                            elapsed: interruption.elapsed,
+                            ..self.failed(
+                                part,
+                                interruption.reason.clone(),
+                                interruption.termination,
+                            )
+                        });
+                    }
+                }
+                None => unreachable!("the steps should end with the last part or an interruption"),
+            }
+        }
+
+        outcome
+    }
+
+    fn failed(&self, part: PuzzlePart, reason: String, termination: Termination) -> PartOutcome {
+        PartOutcome {
+            actual: Err(SolveError::new(reason)
+                .in_year(self.solver.year())
+                .in_puzzle(self.solver.day(), part)),
+            termination,
+            elapsed: Duration::ZERO,
+            cached: false,
+        }
+    }
+}
+
+thread_local! {
+    /// Set while a solver runs on this thread.
+    static SOLVING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Whether a solver runs on the current thread. A panic hook can use it to skip the panics
+/// of solvers, they are reported as part of the [`InputOutcome`] anyway.
+pub fn solver_is_running() -> bool {
+    SOLVING.with(Cell::get)
+}
+
+/// Like [`panic::catch_unwind`], while `f` runs [`solver_is_running`] is set.
+fn catch_solver_panic<R>(f: impl FnOnce() -> R) -> thread::Result<R> {
+    SOLVING.with(|s| s.set(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    SOLVING.with(|s| s.set(false));
+
+    result
+}
+
+fn panic_message(payload: &Box<dyn Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic payload".into()
+    }
+}
+
+/// Runs `f` for every item on up to `jobs` threads. The results are in the order of
+/// the items, no matter in which order the jobs finish.
+pub fn run_ordered<T, R, F>(items: &[T], jobs: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let workers = jobs.clamp(1, items.len().max(1));
+
+    if workers == 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(items.len()));
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+
+                let item = match items.get(index) {
+                    Some(i) => i,
+                    None => break,
+                };
+
+                let result = f(item);
+                results
+                    .lock()
+                    .expect("no worker should panic while holding the lock")
+                    .push((index, result));
+            });
+        }
+    });
+
+    let mut results = results
+        .into_inner()
+        .expect("no worker should panic while holding the lock");
+
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, sync::Arc, thread, time::Duration};
+
+    use super::{run_ordered, InputJob, Termination};
+    use crate::aoc_general::{Answer, PuzzlePart, PuzzleSolver, SolveError, YearSolverCollection};
+
+    #[test]
+    fn results_keep_the_order_of_the_items() {
+        let items = (0..20u64).collect::<Vec<_>>();
+
+        let results = run_ordered(&items, 4, |&i| {
+            // let the first items finish last
+            thread::sleep(Duration::from_millis(20 - i));
+            i * 2
+        });
+
+        assert_eq!(items.iter().map(|i| i * 2).collect::<Vec<_>>(), results);
+        assert!(run_ordered(&[] as &[u8], 4, |&i| i).is_empty());
+    }
+
+    thread_local! {
+        static PARSE_COUNT: Cell<usize> = const { Cell::new(0) };
+    }
+
+    #[derive(Default)]
+    struct MisbehavingDay;
+
+    impl PuzzleSolver for MisbehavingDay {
+        fn year(&self) -> u16 {
+            2022
+        }
+
+        fn day(&self) -> u8 {
+            1
+        }
+
+        /// Whether part 2 is slow.
+        type Model = bool;
+
+        fn parse(&self, input: &[u8]) -> Result<Self::Model, SolveError> {
+            PARSE_COUNT.with(|c| c.set(c.get() + 1));
+            Ok(input == b"slow")
+        }
+
+        fn part1(&self, _: &Self::Model) -> Result<Answer, SolveError> {
+            panic!("index out of bounds")
+        }
+
+        fn part2(&self, slow: &Self::Model) -> Result<Answer, SolveError> {
+            if *slow {
+                thread::sleep(Duration::from_secs(5));
+            }
+
+            Ok(1u8.into())
+        }
+    }
+
+    fn job(parts: &[PuzzlePart], input: &[u8]) -> InputJob {
+        let mut solvers = YearSolverCollection::new(2022);
+        solvers.add::<MisbehavingDay>().unwrap();
+
+        InputJob {
+            solver: solvers.solver(1).unwrap().clone(),
+            parts: parts.to_vec(),
+            input: Arc::from(input),
+        }
+    }
+
+    #[test]
+    fn a_panicking_part_is_reported_as_error() {
+        let outcome = job(&[PuzzlePart::Part1], b"").run(None);
+
+        let error = outcome.parts[0].actual.as_ref().unwrap_err();
+        assert_eq!(Termination::Panicked, outcome.parts[0].termination);
+        assert_eq!("solver panicked: index out of bounds", error.reason);
+        assert_eq!(Some(PuzzlePart::Part1), error.part);
+    }
+
+    #[test]
+    fn parts_running_too_long_are_timed_out() {
+        let job = job(&PuzzlePart::BOTH, b"slow");
+        let outcomes = run_ordered(&[job], 2, |j| j.run(Some(Duration::from_millis(50))));
+        let parts = &outcomes[0].parts;
+
+        assert_eq!(Termination::Panicked, parts[0].termination);
+        assert_eq!(Termination::TimedOut, parts[1].termination);
+        assert_eq!(
+            "timed out after 50ms",
+            parts[1].actual.as_ref().unwrap_err().reason
+        );
+    }
+
+    #[test]
+    fn the_input_is_parsed_once_for_both_parts() {
+        // without a timeout the job runs on the current thread
+        let outcome = job(&PuzzlePart::BOTH, b"").run(None);
+
+        assert_eq!(1, PARSE_COUNT.with(Cell::get));
+        assert_eq!(Termination::Panicked, outcome.parts[0].termination);
+        assert_eq!(Ok(1u8.into()), outcome.parts[1].actual);
+    }
+}
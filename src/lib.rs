@@ -0,0 +1,46 @@
+//! Advent of Code solvers and the tooling to run, verify and benchmark them.
+//!
+//! Every puzzle day implements [`aoc_general::PuzzleSolver`]. The solvers of an event are
+//! collected in a [`aoc_general::YearSolverCollection`] and all years in an
+//! [`aoc_general::AocRegistry`], [`registry`] returns one with everything that is implemented:
+//!
+//! ```
+//! use aoc2022::aoc_general::{Answer, PuzzlePart};
+//!
+//! let registry = aoc2022::registry().unwrap();
+//! let parsed = registry.parse(2022, 1, b"1000\n2000\n\n4000\n").unwrap();
+//!
+//! assert_eq!(
+//!     Ok(Answer::from(4000u32)),
+//!     registry.solve_parsed(&parsed, PuzzlePart::Part1)
+//! );
+//! ```
+//!
+//! [`common`] contains the input helpers the solvers share, the remaining modules are the
+//! building blocks of the `aoc2022` command line tool.
+
+pub mod aoc_general;
+pub mod bench;
+pub mod cache;
+pub mod common;
+pub mod fetch;
+pub mod http;
+pub mod jobs;
+pub mod report;
+pub mod run_all;
+pub mod scaffold;
+pub mod submit;
+pub mod verify;
+pub mod year_2022;
+
+#[cfg(test)]
+mod test_support;
+
+use aoc_general::{AocRegistry, SolveError};
+
+/// A registry with the solvers of all implemented years.
+pub fn registry() -> Result<AocRegistry, SolveError> {
+    let mut registry = AocRegistry::new();
+    registry.add_year(year_2022::solvers()?)?;
+    Ok(registry)
+}
@@ -1,67 +1,226 @@
-mod aoc_general;
-mod common;
-mod year_2022;
+mod cli;
+mod commands;
 
 use std::{
     env,
-    io::{stdin, Read},
+    panic::{self, PanicHookInfo},
+    process::ExitCode,
 };
 
-use crate::{
-    aoc_general::{PuzzlePart, YearSolverCollection},
-    year_2022::{
-        day01::Day1, day02::Day2, day03::Day3, day04::Day4, day05::Day5, day06::Day6, day07::Day7,
-        day08::Day8,
-    },
+use aoc2022::{
+    cache::{AnswerCache, CacheMode},
+    fetch, jobs,
 };
 
-fn main() {
-    let day = get_first_arg();
-
-    let all_std_bytes = stdin()
-        .lock()
-        .bytes()
-        .map(|x| x.expect("reading stdin should not fail"))
-        .collect::<Vec<_>>();
-
-    let mut y2022 = YearSolverCollection::new();
-    y2022.add::<Day1>();
-    y2022.add::<Day2>();
-    y2022.add::<Day3>();
-    y2022.add::<Day4>();
-    y2022.add::<Day5>();
-    y2022.add::<Day6>();
-    y2022.add::<Day7>();
-    y2022.add::<Day8>();
-
-    let result = match day.as_str() {
-        "1a" => (1, PuzzlePart::Part1),
-        "1b" => (1, PuzzlePart::Part2),
-        "2a" => (2, PuzzlePart::Part1),
-        "2b" => (2, PuzzlePart::Part2),
-        "3a" => (3, PuzzlePart::Part1),
-        "3b" => (3, PuzzlePart::Part2),
-        "4a" => (4, PuzzlePart::Part1),
-        "4b" => (4, PuzzlePart::Part2),
-        "5a" => (5, PuzzlePart::Part1),
-        "5b" => (5, PuzzlePart::Part2),
-        "6a" => (6, PuzzlePart::Part1),
-        "6b" => (6, PuzzlePart::Part2),
-        "7a" => (7, PuzzlePart::Part1),
-        "7b" => (7, PuzzlePart::Part2),
-        "8a" => (8, PuzzlePart::Part1),
-        "8b" => (8, PuzzlePart::Part2),
-        x => todo!("day with code '{}' not (yet?) implemented", x),
+use crate::cli::{Command, EXIT_USAGE, USAGE};
+
+fn main() -> ExitCode {
+    let command = match cli::parse_args(env::args().skip(1)) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            eprintln!("Run 'aoc2022 help' for usage.");
+            return ExitCode::from(EXIT_USAGE);
+        }
     };
 
-    let mut input = all_std_bytes.iter().copied();
-    let solution = y2022.solve(result.0, &mut input, result.1);
+    let registry = aoc2022::registry().expect("solvers should register without conflicts");
+    panic::set_hook(Box::new(skip_solver_panics(panic::take_hook())));
+
+    match command {
+        Command::Solve {
+            year,
+            day,
+            parts,
+            input,
+            format,
+            cache,
+        } => {
+            let year = year.or(registry.latest_year()).unwrap_or_default();
+            let cache = cache.open();
+            commands::solve(
+                &registry,
+                year,
+                day,
+                parts,
+                input.as_deref(),
+                format,
+                &cache,
+            )
+        }
+        Command::Verify {
+            data_root,
+            format,
+            jobs,
+            timeout,
+            cache,
+        } => {
+            let jobs = jobs.unwrap_or_else(jobs::default_jobs);
+            commands::verify(&registry, &data_root, format, jobs, timeout, &cache.open())
+        }
+        Command::Bench {
+            year,
+            data_dir,
+            runs,
+            format,
+        } => {
+            let year = year.or(registry.latest_year()).unwrap_or_default();
+            commands::bench(&registry, year, &data_dir, runs, format)
+        }
+        Command::RunAll {
+            year,
+            data_dir,
+            format,
+            jobs,
+            timeout,
+            cache,
+        } => {
+            let year = year.or(registry.latest_year()).unwrap_or_default();
+            let jobs = jobs.unwrap_or_else(jobs::default_jobs);
+            commands::run_all(
+                &registry,
+                year,
+                &data_dir,
+                format,
+                jobs,
+                timeout,
+                &cache.open(),
+            )
+        }
+        Command::Fetch {
+            year,
+            day,
+            user,
+            data_root,
+            website,
+        } => {
+            let year = year.or(registry.latest_year()).unwrap_or_default();
+            let target = fetch::input_path(&data_root, year, &user, day);
+            commands::download_input(&website.open(), year, day, &target)
+        }
+        Command::Submit {
+            year,
+            day,
+            part,
+            user,
+            data_root,
+            website,
+        } => {
+            let year = year.or(registry.latest_year()).unwrap_or_default();
+            let user_dir = fetch::user_dir(&data_root, year, &user);
+            commands::submit(&registry, &website.open(), year, day, part, &user_dir)
+        }
+        Command::Cache { action, dir } => {
+            commands::cache(&registry, action, &AnswerCache::new(dir, CacheMode::Use))
+        }
+        Command::Day7 {
+            action,
+            input,
+            sort,
+            format,
+            mode,
+        } => commands::day7(action, input.as_deref(), sort, format, mode),
+        Command::Day7Generate { source, options } => commands::day7_generate(&source, options),
+        Command::NewDay { year, day, src_dir } => {
+            let year = year.or(registry.latest_year()).unwrap_or_default();
+            commands::new_day(year, day, &src_dir)
+        }
+        Command::List => {
+            for solvers in registry.years() {
+                let days = solvers.days().map(|d| d.to_string()).collect::<Vec<_>>();
+                println!("{}: {}", solvers.year(), days.join(" "));
+            }
+
+            ExitCode::SUCCESS
+        }
+        Command::Help => {
+            print!("{}", USAGE);
+            ExitCode::SUCCESS
+        }
+    }
+}
 
-    println!("The solution is: '{}'", solution);
+/// Wraps the panic hook `previous` so panics of solvers are skipped, verify and run-all report
+/// them with the results. Panics anywhere else still reach `previous`.
+fn skip_solver_panics<F>(previous: F) -> impl Fn(&PanicHookInfo) + Send + Sync + 'static
+where
+    F: Fn(&PanicHookInfo) + Send + Sync + 'static,
+{
+    move |info| {
+        if !jobs::solver_is_running() {
+            previous(info);
+        }
+    }
 }
 
-fn get_first_arg() -> String {
-    env::args()
-        .nth(1)
-        .expect("Expected to find a cli parameter.")
+#[cfg(test)]
+mod tests {
+    use std::{
+        panic,
+        sync::{Arc, Mutex},
+    };
+
+    use aoc2022::{
+        aoc_general::{Answer, PuzzlePart, PuzzleSolver, SolveError, YearSolverCollection},
+        jobs::{InputJob, Termination},
+    };
+
+    use super::skip_solver_panics;
+
+    #[derive(Default)]
+    struct PanickingDay;
+
+    impl PuzzleSolver for PanickingDay {
+        fn year(&self) -> u16 {
+            2022
+        }
+
+        fn day(&self) -> u8 {
+            1
+        }
+
+        type Model = ();
+
+        fn parse(&self, _: &[u8]) -> Result<Self::Model, SolveError> {
+            Ok(())
+        }
+
+        fn part1(&self, _: &Self::Model) -> Result<Answer, SolveError> {
+            panic!("inside of a solver")
+        }
+
+        fn part2(&self, _: &Self::Model) -> Result<Answer, SolveError> {
+            Ok(2u8.into())
+        }
+    }
+
+    #[test]
+    fn only_panics_outside_of_solvers_reach_the_previous_hook() {
+        let mut solvers = YearSolverCollection::new(2022);
+        solvers.add::<PanickingDay>().unwrap();
+
+        let job = InputJob {
+            solver: solvers.solver(1).unwrap().clone(),
+            parts: vec![PuzzlePart::Part1],
+            input: Arc::from(&b""[..]),
+        };
+
+        let printed = Arc::new(Mutex::new(vec![]));
+        let previous = panic::take_hook();
+
+        let recorded = printed.clone();
+        panic::set_hook(Box::new(skip_solver_panics(move |info| {
+            let message = info.payload().downcast_ref::<&str>().copied();
+            recorded.lock().unwrap().extend(message);
+        })));
+
+        let outcome = job.run(None);
+        let outside = panic::catch_unwind(|| panic!("outside of a solver"));
+
+        panic::set_hook(previous);
+
+        assert_eq!(Termination::Panicked, outcome.parts[0].termination);
+        assert!(outside.is_err());
+        assert_eq!(vec!["outside of a solver"], *printed.lock().unwrap());
+    }
 }
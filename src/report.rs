@@ -0,0 +1,222 @@
+//! Machine-readable output of solved parts as JSON, NDJSON or TSV.
+
+use std::{path::Path, time::Duration};
+
+use crate::{
+    aoc_general::{Answer, PuzzlePart, SolveError},
+    common::json_string,
+    verify::{PartResult, VerifyStatus},
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human readable, each command has its own layout.
+    Text,
+    /// A single JSON array with one object per record.
+    Json,
+    /// One JSON object per line.
+    Ndjson,
+    /// Tab separated values with a header line.
+    Tsv,
+}
+
+/// The outcome of a single puzzle part in a form that can be written in every [`OutputFormat`].
+pub struct Record<'a> {
+    pub year: u16,
+    pub day: u8,
+    pub part: PuzzlePart,
+    pub actual: &'a Result<Answer, SolveError>,
+    pub expected: Option<&'a Answer>,
+    /// `None` if there was nothing to compare with, like for `solve`.
+    pub status: Option<VerifyStatus>,
+    pub elapsed: Duration,
+    /// `None` if the input was read from stdin.
+    pub input_path: Option<&'a Path>,
+    pub input_hash: &'a str,
+    /// The answer was taken from the cache instead of solving the part.
+    pub cached: bool,
+}
+
+impl<'a> From<&'a PartResult> for Record<'a> {
+    fn from(r: &'a PartResult) -> Self {
+        Record {
+            year: r.year,
+            day: r.day,
+            part: r.part,
+            actual: &r.actual,
+            expected: r.expected.as_ref(),
+            status: Some(r.status),
+            elapsed: r.elapsed,
+            input_path: Some(&r.input_path),
+            input_hash: &r.input_hash,
+            cached: r.cached,
+        }
+    }
+}
+
+const TSV_HEADER: &str = "year\tday\tpart\tanswer\tanswer_type\texpected\tstatus\telapsed_ns\tinput_path\tinput_hash\tcached\terror";
+
+/// The records in `format`. [`OutputFormat::Text`] has one `<year> day <day> part <n>: <answer>`
+/// line per record, or `Error: <error>` if the part failed.
+pub fn format_records(records: &[Record], format: OutputFormat) -> String {
+    let lines = match format {
+        OutputFormat::Text => records.iter().map(to_text).collect::<Vec<_>>(),
+        OutputFormat::Json => {
+            let objects = records.iter().map(to_json).collect::<Vec<_>>();
+            vec![format!("[{}]", objects.join(","))]
+        }
+        OutputFormat::Ndjson => records.iter().map(to_json).collect(),
+        OutputFormat::Tsv => [TSV_HEADER.to_string()]
+            .into_iter()
+            .chain(records.iter().map(to_tsv))
+            .collect(),
+    };
+
+    lines.iter().map(|l| format!("{}\n", l)).collect()
+}
+
+/// A single record in [`OutputFormat::Text`].
+pub fn to_text(r: &Record) -> String {
+    match r.actual {
+        Ok(a) => format!("{} day {} {}: {}", r.year, r.day, r.part, a),
+        Err(e) => format!("Error: {}", e),
+    }
+}
+
+fn to_json(r: &Record) -> String {
+    let (answer, answer_type, error) = match r.actual {
+        Ok(a) => (
+            json_string(&a.to_string()),
+            json_string(a.type_name()),
+            "null".into(),
+        ),
+        Err(e) => ("null".into(), "null".into(), error_to_json(e)),
+    };
+
+    let optional = |value: Option<String>| value.unwrap_or_else(|| "null".into());
+
+    format!(
+        "{{\"year\":{},\"day\":{},\"part\":{},\"answer\":{},\"answer_type\":{},\"expected\":{},\"status\":{},\"elapsed_ns\":{},\"input_path\":{},\"input_hash\":{},\"cached\":{},\"error\":{}}}",
+        r.year,
+        r.day,
+        r.part.number(),
+        answer,
+        answer_type,
+        optional(r.expected.map(|e| json_string(&e.to_string()))),
+        optional(r.status.map(|s| json_string(&s.to_string().to_lowercase()))),
+        r.elapsed.as_nanos(),
+        optional(r.input_path.map(|p| json_string(&p.display().to_string()))),
+        json_string(r.input_hash),
+        r.cached,
+        error
+    )
+}
+
+fn error_to_json(e: &SolveError) -> String {
+    let optional = |value: Option<usize>| value.map_or("null".into(), |v| v.to_string());
+
+    format!(
+        "{{\"message\":{},\"reason\":{},\"line\":{},\"column\":{}}}",
+        json_string(&e.to_string()),
+        json_string(&e.reason),
+        optional(e.line),
+        optional(e.column)
+    )
+}
+
+fn to_tsv(r: &Record) -> String {
+    let (answer, answer_type, error) = match r.actual {
+        Ok(a) => (a.to_string(), a.type_name(), String::new()),
+        Err(e) => (String::new(), "", e.to_string()),
+    };
+
+    [
+        r.year.to_string(),
+        r.day.to_string(),
+        r.part.number().to_string(),
+        answer,
+        answer_type.to_string(),
+        r.expected.map(|e| e.to_string()).unwrap_or_default(),
+        r.status
+            .map(|s| s.to_string().to_lowercase())
+            .unwrap_or_default(),
+        r.elapsed.as_nanos().to_string(),
+        r.input_path
+            .map(|p| p.display().to_string())
+            .unwrap_or_default(),
+        r.input_hash.to_string(),
+        r.cached.to_string(),
+        error,
+    ]
+    .map(|field| tsv_field(&field))
+    .join("\t")
+}
+
+/// Multi-line answers and error messages must not break the row structure.
+fn tsv_field(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, time::Duration};
+
+    use super::{to_json, to_tsv, tsv_field, Record};
+    use crate::{
+        aoc_general::{Answer, PuzzlePart, SolveError},
+        verify::VerifyStatus,
+    };
+
+    #[test]
+    fn tsv_fields_stay_on_one_line() {
+        assert_eq!(r"#..\n.#\tx", tsv_field("#..\n.#\tx"));
+    }
+
+    #[test]
+    fn records_contain_answers_and_errors() {
+        let answer = Ok(Answer::from(24000u32));
+        let expected = Answer::from(24000u32);
+
+        let record = Record {
+            year: 2022,
+            day: 1,
+            part: PuzzlePart::Part1,
+            actual: &answer,
+            expected: Some(&expected),
+            status: Some(VerifyStatus::Pass),
+            elapsed: Duration::from_nanos(1500),
+            input_path: Some(Path::new("data/01-input.txt")),
+            input_hash: "cbf29ce484222325",
+            cached: false,
+        };
+
+        assert_eq!(
+            r#"{"year":2022,"day":1,"part":1,"answer":"24000","answer_type":"unsigned","expected":"24000","status":"pass","elapsed_ns":1500,"input_path":"data/01-input.txt","input_hash":"cbf29ce484222325","cached":false,"error":null}"#,
+            to_json(&record)
+        );
+
+        let error = Err(SolveError::new("bad").at_line(3).in_year(2022));
+
+        let record = Record {
+            actual: &error,
+            expected: None,
+            status: None,
+            input_path: None,
+            cached: true,
+            ..record
+        };
+
+        assert_eq!(
+            r#"{"year":2022,"day":1,"part":1,"answer":null,"answer_type":null,"expected":null,"status":null,"elapsed_ns":1500,"input_path":null,"input_hash":"cbf29ce484222325","cached":true,"error":{"message":"2022: line 3: bad","reason":"bad","line":3,"column":null}}"#,
+            to_json(&record)
+        );
+        assert_eq!(
+            "2022\t1\t1\t\t\t\t\t1500\t\tcbf29ce484222325\ttrue\t2022: line 3: bad",
+            to_tsv(&record)
+        );
+    }
+}
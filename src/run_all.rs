@@ -0,0 +1,165 @@
+//! Solving every registered day of a single data directory.
+
+use std::{fs, io, path::Path, time::Duration};
+
+use crate::{
+    aoc_general::{Answer, YearSolverCollection},
+    cache::AnswerCache,
+    verify::{self, ExpectedInput, PartResult, VerifyStatus},
+};
+
+pub struct RunSummary {
+    pub parts: Vec<PartResult>,
+    /// Registered days without an `NN-input.txt` in the data directory.
+    pub days_without_input: Vec<u8>,
+    /// Every input is parsed once for both parts, inputs with only cached answers not at all.
+    pub parse_elapsed: Duration,
+}
+
+impl RunSummary {
+    pub fn count(&self, status: VerifyStatus) -> usize {
+        self.parts.iter().filter(|p| p.status == status).count()
+    }
+
+    pub fn cached(&self) -> usize {
+        self.parts.iter().filter(|p| p.cached).count()
+    }
+
+    pub fn has_failures(&self) -> bool {
+        self.parts.iter().any(|p| p.status.is_failure())
+    }
+}
+
+/// Runs both parts of every registered day that has an `NN-input.txt` in `data_dir` and
+/// compares them with the matching `NN-output.txt` if there is one, the results are ordered
+/// by day and part. See [`verify::solve_inputs`] for how the inputs are solved.
+pub fn run_all(
+    solvers: &YearSolverCollection,
+    data_dir: &Path,
+    jobs: usize,
+    timeout: Option<Duration>,
+    cache: &AnswerCache,
+) -> io::Result<RunSummary> {
+    let mut days_without_input = vec![];
+    let mut inputs = vec![];
+
+    for solver in solvers.day_solvers() {
+        let day = solver.day();
+        let input_path = data_dir.join(format!("{day:02}-input.txt"));
+
+        if !input_path.is_file() {
+            days_without_input.push(day);
+            continue;
+        }
+
+        inputs.push(ExpectedInput {
+            solver: solver.clone(),
+            user: None,
+            input: fs::read(&input_path)?.into(),
+            expected: verify::read_expected(&data_dir.join(format!("{day:02}-output.txt")))?,
+            input_path,
+        });
+    }
+
+    let (parts, parse_elapsed) = verify::solve_inputs(&inputs, jobs, timeout, cache);
+
+    Ok(RunSummary {
+        parts,
+        days_without_input,
+        parse_elapsed,
+    })
+}
+
+/// The `run-all` table with a line per part, followed by the counts of each status and the
+/// total time.
+pub fn format_table(summary: &RunSummary) -> String {
+    let rows = summary
+        .parts
+        .iter()
+        .map(|p| {
+            let answer = match &p.actual {
+                Ok(a) => a.single_line(),
+                Err(e) => e.to_string(),
+            };
+
+            let expected = p
+                .expected
+                .as_ref()
+                .map(Answer::single_line)
+                .unwrap_or_default();
+
+            (p, answer, expected)
+        })
+        .collect::<Vec<_>>();
+
+    let width = |header: &str, column: fn(&(&PartResult, String, String)) -> usize| {
+        rows.iter()
+            .map(column)
+            .chain([header.len()])
+            .max()
+            .unwrap_or_default()
+    };
+
+    let answer_width = width("answer", |r| r.1.len());
+    let expected_width = width("expected", |r| r.2.len());
+
+    let mut table = format!(
+        "year  day  part  {:<answer_width$}  {:<expected_width$}  status   {:>12}\n",
+        "answer", "expected", "time"
+    );
+
+    for (p, answer, expected) in &rows {
+        let part = p.part.number();
+
+        table.push_str(&format!(
+            "{}  {:>3}  {:>4}  {:<answer_width$}  {:<expected_width$}  {:<7}  {:>12}\n",
+            p.year,
+            p.day,
+            part,
+            answer,
+            expected,
+            p.status.to_string(),
+            if p.cached {
+                "cached".into()
+            } else {
+                format!("{:.3?}", p.elapsed)
+            }
+        ));
+    }
+
+    let parts_elapsed = summary.parts.iter().map(|p| p.elapsed).sum::<Duration>();
+
+    table.push_str(&format!(
+        "{} parts: {} passed, {} failed, {} errors, {} panics, {} timeouts, {} missing expected\n",
+        summary.parts.len(),
+        summary.count(VerifyStatus::Pass),
+        summary.count(VerifyStatus::Fail),
+        summary.count(VerifyStatus::Error),
+        summary.count(VerifyStatus::Panic),
+        summary.count(VerifyStatus::Timeout),
+        summary.count(VerifyStatus::MissingExpected)
+    ));
+
+    if summary.cached() > 0 {
+        table.push_str(&format!("cached answers: {}\n", summary.cached()));
+    }
+
+    if !summary.days_without_input.is_empty() {
+        let days = summary
+            .days_without_input
+            .iter()
+            .map(|d| d.to_string())
+            .collect::<Vec<_>>();
+
+        table.push_str(&format!("days without input: {}\n", days.join(" ")));
+    }
+
+    table.push_str(&format!(
+        "total time: {:.3?} (parse {:.3?}, parts {:.3?})\n",
+        summary.parse_elapsed + parts_elapsed,
+        summary.parse_elapsed,
+        parts_elapsed
+    ));
+
+    table
+}
@@ -0,0 +1,237 @@
+//! Generating the module of a new puzzle day and registering it with its year.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// `__YEAR__`, `__DAY__` and `__DD__` (the day with two digits) are replaced.
+const DAY_TEMPLATE: &str = r#"use crate::{
+    aoc_general::{Answer, PuzzleSolver, SolveError},
+    common::{LfEofDropable, LineSplittable, NormalizeLineBreaks},
+};
+
+#[derive(Default)]
+pub struct Day__DAY__;
+
+impl PuzzleSolver for Day__DAY__ {
+    fn year(&self) -> u16 {
+        __YEAR__
+    }
+
+    fn day(&self) -> u8 {
+        __DAY__
+    }
+
+    /// The lines of the input.
+    type Model = Vec<Vec<u8>>;
+
+    fn parse(&self, input: &[u8]) -> Result<Self::Model, SolveError> {
+        Ok(input
+            .iter()
+            .copied()
+            .normalize_line_breaks()
+            .split_lf_line_breaks()
+            .drop_lf_eof()
+            .collect())
+    }
+
+    fn part1(&self, _lines: &Self::Model) -> Result<Answer, SolveError> {
+        Err(SolveError::new("part 1 is not solved yet"))
+    }
+
+    fn part2(&self, _lines: &Self::Model) -> Result<Answer, SolveError> {
+        Err(SolveError::new("part 2 is not solved yet"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::Day__DAY__;
+    use crate::{aoc_general::PuzzleSolver, verify};
+
+    const EXAMPLES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/data/__YEAR__/examples");
+
+    #[test]
+    #[ignore = "add the example input"]
+    fn example_answers_match_the_expected_output() {
+        let input = fs::read(Path::new(EXAMPLES).join("__DD__-input.txt"))
+            .expect("the example should be in data/__YEAR__/examples");
+        let expected = verify::read_expected(&Path::new(EXAMPLES).join("__DD__-output.txt")).unwrap();
+
+        let model = Day__DAY__.parse(&input).unwrap();
+
+        if let Some(e) = &expected[0] {
+            assert_eq!(Ok(e.clone()), Day__DAY__.part1(&model));
+        }
+
+        if let Some(e) = &expected[1] {
+            assert_eq!(Ok(e.clone()), Day__DAY__.part2(&model));
+        }
+    }
+}
+"#;
+
+/// The source of a new day module, solving nothing yet.
+pub fn day_module(year: u16, day: u8) -> String {
+    DAY_TEMPLATE
+        .replace("__YEAR__", &year.to_string())
+        .replace("__DD__", &format!("{day:02}"))
+        .replace("__DAY__", &day.to_string())
+}
+
+/// Writes `<src_dir>/year_<year>/dayNN.rs` and registers it in the `mod.rs` next to it.
+/// Existing modules are never overwritten.
+pub fn add_day(src_dir: &Path, year: u16, day: u8) -> io::Result<PathBuf> {
+    let year_dir = src_dir.join(format!("year_{year}"));
+    let mod_path = year_dir.join("mod.rs");
+    let day_path = year_dir.join(format!("day{day:02}.rs"));
+
+    if !mod_path.is_file() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "{} doesn't exist, a new year has to be added to lib.rs by hand",
+                mod_path.display()
+            ),
+        ));
+    }
+
+    if day_path.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", day_path.display()),
+        ));
+    }
+
+    let registered = register_day(&fs::read_to_string(&mod_path)?, day).map_err(|reason| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {}", mod_path.display(), reason),
+        )
+    })?;
+
+    fs::write(&day_path, day_module(year, day))?;
+    fs::write(&mod_path, registered)?;
+
+    Ok(day_path)
+}
+
+/// Adds `pub mod dayNN;` and the `add::<dayNN::DayN>()` call to the source of a year's
+/// `mod.rs`, both in the order of the days. The existing lines are used as pattern.
+fn register_day(mod_source: &str, day: u8) -> Result<String, String> {
+    let mut lines = mod_source.lines().map(String::from).collect::<Vec<_>>();
+
+    let module_day = |line: &str| -> Option<u8> {
+        line.trim()
+            .strip_prefix("pub mod day")?
+            .strip_suffix(';')?
+            .parse()
+            .ok()
+    };
+
+    let registered_day = |line: &str| -> Option<u8> {
+        let (_, rest) = line.split_once(".add::<day")?;
+        rest.get(..2)?.parse().ok()
+    };
+
+    if lines.iter().any(|l| module_day(l) == Some(day)) {
+        return Err(format!("day {} is already declared", day));
+    }
+
+    let module_position = insert_position(&lines, module_day, day)
+        .ok_or("no 'pub mod dayNN;' line to add the new day after")?;
+
+    lines.insert(module_position, format!("pub mod day{day:02};"));
+
+    let registration_position = insert_position(&lines, registered_day, day)
+        .ok_or("no '.add::<dayNN::DayN>()?;' line to add the new day after")?;
+
+    // the receiver and indentation are taken from an existing registration
+    let pattern = &lines[registration_position.saturating_sub(1).max(
+        lines
+            .iter()
+            .position(|l| registered_day(l).is_some())
+            .unwrap_or_default(),
+    )];
+    let receiver = pattern.split(".add::<").next().unwrap_or_default();
+
+    lines.insert(
+        registration_position,
+        format!("{receiver}.add::<day{day:02}::Day{day}>()?;"),
+    );
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// The index of the line after the last matching line with a lower day, or before the first
+/// matching line if all have a higher day. `None` if no line matches.
+fn insert_position<F>(lines: &[String], day_of: F, day: u8) -> Option<usize>
+where
+    F: Fn(&str) -> Option<u8>,
+{
+    let matching = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, l)| day_of(l).map(|d| (i, d)))
+        .collect::<Vec<_>>();
+
+    match matching.iter().rev().find(|(_, d)| *d < day) {
+        Some((i, _)) => Some(i + 1),
+        None => matching.first().map(|(i, _)| *i),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{day_module, register_day};
+
+    const MOD_RS: &str = "\
+pub mod day01;
+pub mod day03;
+
+pub fn solvers() -> Result<YearSolverCollection, SolveError> {
+    let mut y2022 = YearSolverCollection::new(2022);
+
+    y2022.add::<day01::Day1>()?;
+    y2022.add::<day03::Day3>()?;
+
+    Ok(y2022)
+}
+";
+
+    #[test]
+    fn new_days_are_registered_in_order() {
+        let registered = register_day(MOD_RS, 2).unwrap();
+
+        assert_eq!(
+            MOD_RS
+                .replace("pub mod day03;", "pub mod day02;\npub mod day03;")
+                .replace(
+                    "    y2022.add::<day03::Day3>()?;",
+                    "    y2022.add::<day02::Day2>()?;\n    y2022.add::<day03::Day3>()?;"
+                ),
+            registered
+        );
+
+        let registered = register_day(MOD_RS, 12).unwrap();
+        assert!(registered.contains("pub mod day03;\npub mod day12;\n"));
+        assert!(registered.contains("<day03::Day3>()?;\n    y2022.add::<day12::Day12>()?;\n"));
+
+        assert!(register_day(MOD_RS, 3).is_err());
+        assert!(register_day("pub fn solvers() {}\n", 3).is_err());
+    }
+
+    #[test]
+    fn the_template_is_filled_in() {
+        let module = day_module(2022, 9);
+
+        assert!(module.contains("pub struct Day9;"));
+        assert!(module.contains("/data/2022/examples\");"));
+        assert!(module.contains(".join(\"09-input.txt\")"));
+        assert!(module.contains("#[ignore = \"add the example input\"]"));
+        assert!(!module.contains("__"));
+    }
+}
@@ -0,0 +1,516 @@
+//! Submitting answers to the website, guarded by a local log of earlier submissions.
+
+use std::{
+    fmt, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    aoc_general::{Answer, PuzzlePart},
+    http::{self, AocWebsite},
+    verify,
+};
+
+/// Name of the submission log in the directory of a user's inputs.
+pub const LOG_FILE_NAME: &str = "submissions.tsv";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SubmitOutcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong without a hint in which direction.
+    Wrong,
+    /// The previous submission was too recent, the answer wasn't checked.
+    Wait,
+    /// The part was already solved, the answer wasn't checked.
+    AlreadySolved,
+    /// The response couldn't be understood.
+    Unknown,
+}
+
+const OUTCOMES: [SubmitOutcome; 7] = [
+    SubmitOutcome::Correct,
+    SubmitOutcome::TooHigh,
+    SubmitOutcome::TooLow,
+    SubmitOutcome::Wrong,
+    SubmitOutcome::Wait,
+    SubmitOutcome::AlreadySolved,
+    SubmitOutcome::Unknown,
+];
+
+impl SubmitOutcome {
+    /// Whether the website checked the answer and rejected it.
+    pub fn is_wrong(self) -> bool {
+        matches!(
+            self,
+            SubmitOutcome::TooHigh | SubmitOutcome::TooLow | SubmitOutcome::Wrong
+        )
+    }
+}
+
+impl fmt::Display for SubmitOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitOutcome::Correct => write!(f, "correct"),
+            SubmitOutcome::TooHigh => write!(f, "too-high"),
+            SubmitOutcome::TooLow => write!(f, "too-low"),
+            SubmitOutcome::Wrong => write!(f, "wrong"),
+            SubmitOutcome::Wait => write!(f, "wait"),
+            SubmitOutcome::AlreadySolved => write!(f, "already-solved"),
+            SubmitOutcome::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// The response of the website to a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubmitResponse {
+    pub outcome: SubmitOutcome,
+    /// How long to wait before the next submission, if the website said so.
+    pub cooldown: Option<Duration>,
+    /// The text of the response without HTML markup.
+    pub message: String,
+}
+
+impl SubmitResponse {
+    /// Reads the outcome from the `<article>` of the HTML page the website answers with.
+    pub fn parse(html: &str) -> Self {
+        let article = html
+            .split_once("<article")
+            .and_then(|(_, rest)| rest.split_once("</article>"))
+            .map_or(html, |(article, _)| article);
+
+        let message = html_to_text(article.split_once('>').map_or(article, |(_, a)| a));
+
+        let outcome = if message.contains("That's the right answer") {
+            SubmitOutcome::Correct
+        } else if message.contains("That's not the right answer") {
+            if message.contains("too high") {
+                SubmitOutcome::TooHigh
+            } else if message.contains("too low") {
+                SubmitOutcome::TooLow
+            } else {
+                SubmitOutcome::Wrong
+            }
+        } else if message.contains("You gave an answer too recently") {
+            SubmitOutcome::Wait
+        } else if message.contains("You don't seem to be solving the right level") {
+            SubmitOutcome::AlreadySolved
+        } else {
+            SubmitOutcome::Unknown
+        };
+
+        SubmitResponse {
+            outcome,
+            cooldown: parse_cooldown(&message),
+            message,
+        }
+    }
+}
+
+fn html_to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    let text = text
+        .replace("&apos;", "'")
+        .replace("&#39;", "'")
+        .replace("&quot;", "\"")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&");
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Understands "You have 1m 35s left to wait" and "please wait one minute" / "wait 5 minutes".
+fn parse_cooldown(message: &str) -> Option<Duration> {
+    if let Some((_, rest)) = message.split_once("You have ") {
+        if let Some((left, _)) = rest.split_once(" left to wait") {
+            let mut seconds = 0;
+
+            for part in left.split_whitespace() {
+                let (value, unit) = part.split_at(part.len().saturating_sub(1));
+                let factor = match unit {
+                    "h" => 3600,
+                    "m" => 60,
+                    "s" => 1,
+                    _ => return None,
+                };
+
+                seconds += value.parse::<u64>().ok()? * factor;
+            }
+
+            return Some(Duration::from_secs(seconds));
+        }
+    }
+
+    let lowercase = message.to_lowercase();
+    let mut words = lowercase.split_once("wait ")?.1.split_whitespace();
+
+    let amount = match words.next()? {
+        "one" | "a" => 1,
+        n => n.parse::<u64>().ok()?,
+    };
+
+    match words.next()? {
+        w if w.starts_with("minute") => Some(Duration::from_secs(amount * 60)),
+        w if w.starts_with("second") => Some(Duration::from_secs(amount)),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    /// Seconds since the Unix epoch.
+    pub time: u64,
+    pub day: u8,
+    pub part: PuzzlePart,
+    pub answer: String,
+    pub outcome: SubmitOutcome,
+    /// No answer may be submitted before this time, seconds since the Unix epoch.
+    pub retry_after: Option<u64>,
+}
+
+/// Every submission of a user, one tab separated line each:
+/// `<time> <day> <part> <outcome> <retry after> <answer>`.
+pub struct SubmissionLog {
+    path: PathBuf,
+    entries: Vec<Submission>,
+}
+
+impl SubmissionLog {
+    /// Reads the log, a missing file is an empty log.
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+
+        let content = match fs::read_to_string(&path) {
+            Ok(c) => c,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+
+        let entries = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(i, line)| {
+                parse_submission(line).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("{}: line {}: invalid submission", path.display(), i + 1),
+                    )
+                })
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        Ok(SubmissionLog { path, entries })
+    }
+
+    pub fn entries(&self) -> &[Submission] {
+        &self.entries
+    }
+
+    /// The reason why `answer` must not be submitted at time `now`, if there is one.
+    pub fn refusal(&self, day: u8, part: PuzzlePart, answer: &str, now: u64) -> Option<String> {
+        let same_part = || {
+            self.entries
+                .iter()
+                .filter(move |s| s.day == day && s.part == part)
+        };
+
+        if let Some(s) = same_part().find(|s| s.outcome == SubmitOutcome::Correct) {
+            return Some(format!("{} was already solved with {}", part, s.answer));
+        }
+
+        if same_part().any(|s| s.outcome == SubmitOutcome::AlreadySolved) {
+            return Some(format!("the website reported {} as already solved", part));
+        }
+
+        // the website enforces the cooldown for the whole account
+        if let Some(retry_after) = self.entries.iter().filter_map(|s| s.retry_after).max() {
+            if retry_after > now {
+                return Some(format!(
+                    "the website asked to wait, {}s left",
+                    retry_after - now
+                ));
+            }
+        }
+
+        if let Some(s) = same_part().find(|s| s.outcome.is_wrong() && s.answer == answer) {
+            return Some(format!(
+                "{} was already submitted and was {}",
+                answer, s.outcome
+            ));
+        }
+
+        let value = answer.parse::<i128>().ok()?;
+
+        same_part().find_map(|s| {
+            let bound = s.answer.parse::<i128>().ok()?;
+
+            match s.outcome {
+                SubmitOutcome::TooHigh if value >= bound => Some(format!(
+                    "{} is not lower than {}, which was too high",
+                    answer, bound
+                )),
+                SubmitOutcome::TooLow if value <= bound => Some(format!(
+                    "{} is not higher than {}, which was too low",
+                    answer, bound
+                )),
+                _ => None,
+            }
+        })
+    }
+
+    /// Appends the submission to the log file.
+    pub fn record(&mut self, submission: Submission) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+
+        let part = submission.part.number();
+
+        writeln!(
+            file,
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            submission.time,
+            submission.day,
+            part,
+            submission.outcome,
+            submission
+                .retry_after
+                .map(|r| r.to_string())
+                .unwrap_or_default(),
+            submission.answer
+        )?;
+
+        self.entries.push(submission);
+        Ok(())
+    }
+}
+
+fn parse_submission(line: &str) -> Option<Submission> {
+    let mut fields = line.splitn(6, '\t');
+
+    let time = fields.next()?.parse().ok()?;
+    let day = fields.next()?.parse().ok()?;
+
+    let part = match fields.next()? {
+        "1" => PuzzlePart::Part1,
+        "2" => PuzzlePart::Part2,
+        _ => return None,
+    };
+
+    let outcome = fields.next()?;
+    let outcome = OUTCOMES.into_iter().find(|o| o.to_string() == outcome)?;
+
+    let retry_after = match fields.next()? {
+        "" => None,
+        r => Some(r.parse().ok()?),
+    };
+
+    Some(Submission {
+        time,
+        day,
+        part,
+        answer: fields.next()?.to_string(),
+        outcome,
+        retry_after,
+    })
+}
+
+pub enum Submitted {
+    /// The answer wasn't sent, because of what the log says.
+    Refused(String),
+    Sent(SubmitResponse),
+}
+
+/// Submits `answer` unless the log shows that it's pointless or the website asked to wait.
+/// Every response is recorded in the log, a correct answer is also written to the
+/// `NN-output.txt` at `output_path`.
+pub fn submit_answer(
+    website: &AocWebsite,
+    log: &mut SubmissionLog,
+    year: u16,
+    day: u8,
+    part: PuzzlePart,
+    answer: &Answer,
+    output_path: &Path,
+) -> io::Result<Submitted> {
+    if let Answer::Grid(_) = answer {
+        return Ok(Submitted::Refused(
+            "grid answers have to be read and submitted by hand".into(),
+        ));
+    }
+
+    let answer_text = answer.to_string();
+    let now = unix_seconds();
+
+    if let Some(reason) = log.refusal(day, part, &answer_text, now) {
+        return Ok(Submitted::Refused(reason));
+    }
+
+    let level = part.number();
+
+    let body = website.post_form(
+        &format!("/{}/day/{}/answer", year, day),
+        format!("level={}&answer={}", level, http::form_encode(&answer_text)),
+    )?;
+
+    let response = SubmitResponse::parse(&String::from_utf8_lossy(&body));
+
+    log.record(Submission {
+        time: now,
+        day,
+        part,
+        answer: answer_text,
+        outcome: response.outcome,
+        retry_after: response.cooldown.map(|c| now + c.as_secs()),
+    })?;
+
+    if response.outcome == SubmitOutcome::Correct {
+        verify::write_expected(output_path, part, answer)?;
+    }
+
+    Ok(Submitted::Sent(response))
+}
+
+fn unix_seconds() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process, time::Duration};
+
+    use super::{Submission, SubmissionLog, SubmitOutcome, SubmitResponse};
+    use crate::aoc_general::PuzzlePart;
+
+    #[test]
+    fn responses_are_classified() {
+        let right = SubmitResponse::parse(
+            "<main><article><p>That&apos;s the right answer!  You are <em>one gold star</em> closer.</p></article></main>",
+        );
+        assert_eq!(SubmitOutcome::Correct, right.outcome);
+        assert_eq!(
+            "That's the right answer! You are one gold star closer.",
+            right.message
+        );
+
+        let too_high = SubmitResponse::parse(
+            "<article><p>That's not the right answer; your answer is too high.  Please wait one minute before trying again.</p></article>",
+        );
+        assert_eq!(SubmitOutcome::TooHigh, too_high.outcome);
+        assert_eq!(Some(Duration::from_secs(60)), too_high.cooldown);
+
+        let wrong = SubmitResponse::parse(
+            "<article><p>That's not the right answer.  Because you have guessed incorrectly 4 times on this puzzle, please wait 5 minutes before trying again.</p></article>",
+        );
+        assert_eq!(SubmitOutcome::Wrong, wrong.outcome);
+        assert_eq!(Some(Duration::from_secs(300)), wrong.cooldown);
+
+        let wait = SubmitResponse::parse(
+            "<article><p>You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 35s left to wait.</p></article>",
+        );
+        assert_eq!(SubmitOutcome::Wait, wait.outcome);
+        assert_eq!(Some(Duration::from_secs(95)), wait.cooldown);
+
+        let solved = SubmitResponse::parse(
+            "<article><p>You don't seem to be solving the right level.  Did you already complete it?</p></article>",
+        );
+        assert_eq!(SubmitOutcome::AlreadySolved, solved.outcome);
+        assert_eq!(None, solved.cooldown);
+
+        assert_eq!(
+            SubmitOutcome::Unknown,
+            SubmitResponse::parse("<html>maintenance</html>").outcome
+        );
+    }
+
+    fn submission(part: PuzzlePart, answer: &str, outcome: SubmitOutcome) -> Submission {
+        Submission {
+            time: 1000,
+            day: 1,
+            part,
+            answer: answer.into(),
+            outcome,
+            retry_after: Some(1060),
+        }
+    }
+
+    #[test]
+    fn known_wrong_answers_and_cooldowns_are_refused() {
+        let path = env::temp_dir().join(format!("aoc-submissions-{}.tsv", process::id()));
+        _ = fs::remove_file(&path);
+
+        let mut log = SubmissionLog::open(&path).unwrap();
+        let part1 = PuzzlePart::Part1;
+
+        log.record(submission(part1, "100", SubmitOutcome::TooHigh))
+            .unwrap();
+        log.record(submission(part1, "10", SubmitOutcome::TooLow))
+            .unwrap();
+        log.record(submission(part1, "abc", SubmitOutcome::Wrong))
+            .unwrap();
+
+        let log = SubmissionLog::open(&path).unwrap();
+        assert_eq!(3, log.entries().len());
+        assert_eq!(
+            submission(part1, "abc", SubmitOutcome::Wrong),
+            log.entries()[2]
+        );
+
+        let refusal = |answer, now| log.refusal(1, part1, answer, now);
+
+        assert_eq!(
+            Some("the website asked to wait, 10s left".into()),
+            refusal("50", 1050)
+        );
+        assert_eq!(
+            Some("150 is not lower than 100, which was too high".into()),
+            refusal("150", 2000)
+        );
+        assert_eq!(
+            Some("5 is not higher than 10, which was too low".into()),
+            refusal("5", 2000)
+        );
+        assert_eq!(
+            Some("abc was already submitted and was wrong".into()),
+            refusal("abc", 2000)
+        );
+        assert_eq!(None, refusal("50", 2000));
+        assert_eq!(None, log.refusal(1, PuzzlePart::Part2, "150", 2000));
+
+        fs::write(&path, "1000\t1\t1\tcorrect\t\t42\n").unwrap();
+        let log = SubmissionLog::open(&path).unwrap();
+        assert_eq!(
+            Some("part 1 was already solved with 42".into()),
+            log.refusal(1, part1, "43", 2000)
+        );
+
+        fs::write(&path, "1000\t1\t3\tcorrect\t\t42\n").unwrap();
+        assert!(SubmissionLog::open(&path).is_err());
+
+        _ = fs::remove_file(&path);
+    }
+}
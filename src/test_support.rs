@@ -0,0 +1,71 @@
+//! A local stand-in for the website, shared by the unit and the integration tests.
+
+use std::{
+    io::{Read, Write},
+    net::{TcpListener, TcpStream},
+    thread::{self, JoinHandle},
+};
+
+/// Answers one request per raw HTTP response, in order, and returns the requests it received.
+/// The first value is the base URL of the server.
+pub fn fake_server(responses: Vec<String>) -> (String, JoinHandle<Vec<String>>) {
+    fake_server_on("127.0.0.1:0", responses)
+}
+
+/// A [`fake_server`] listening on `address`, like `[::1]:0` for IPv6.
+pub fn fake_server_on(address: &str, responses: Vec<String>) -> (String, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind(address).unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+    let server = thread::spawn(move || {
+        responses
+            .iter()
+            .map(|response| {
+                let (mut stream, _) = listener.accept().unwrap();
+                let request = read_request(&mut stream);
+
+                stream.write_all(response.as_bytes()).unwrap();
+                request
+            })
+            .collect()
+    });
+
+    (base_url, server)
+}
+
+/// Reads until the end of the head and the `Content-Length` of the body if there is one.
+fn read_request(stream: &mut TcpStream) -> String {
+    let mut request = vec![];
+    let mut buffer = [0; 4096];
+
+    loop {
+        let length = stream.read(&mut buffer).unwrap();
+        request.extend_from_slice(&buffer[..length]);
+
+        let text = String::from_utf8_lossy(&request).to_string();
+
+        let complete = text.split_once("\r\n\r\n").is_some_and(|(head, body)| {
+            let content_length = head
+                .lines()
+                .filter_map(|l| l.split_once(':'))
+                .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+                .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+                .unwrap_or_default();
+
+            body.len() >= content_length
+        });
+
+        if complete || length == 0 {
+            return text;
+        }
+    }
+}
+
+/// A `200 OK` response with `body`.
+pub fn ok(body: &str) -> String {
+    format!(
+        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+        body.len(),
+        body
+    )
+}
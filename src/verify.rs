@@ -0,0 +1,371 @@
+//! Comparing the answers of the solvers with the expected `NN-output.txt` files.
+
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::{
+    aoc_general::{Answer, AocRegistry, DaySolver, PuzzlePart, SolveError, YearSolverCollection},
+    cache::AnswerCache,
+    common,
+    jobs::{self, InputJob, PartOutcome, Termination},
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VerifyStatus {
+    Pass,
+    Fail,
+    Error,
+    MissingExpected,
+    Panic,
+    Timeout,
+}
+
+impl VerifyStatus {
+    /// Like [`Self::compare`] but panics and timeouts of the job get their own status.
+    pub fn of(outcome: &PartOutcome, expected: Option<&Answer>) -> Self {
+        match outcome.termination {
+            Termination::Completed => VerifyStatus::compare(&outcome.actual, expected),
+            Termination::Panicked => VerifyStatus::Panic,
+            Termination::TimedOut => VerifyStatus::Timeout,
+        }
+    }
+
+    /// Whether the status means that the part wasn't solved correctly.
+    pub fn is_failure(self) -> bool {
+        matches!(
+            self,
+            VerifyStatus::Fail | VerifyStatus::Error | VerifyStatus::Panic | VerifyStatus::Timeout
+        )
+    }
+
+    pub fn compare(actual: &Result<Answer, SolveError>, expected: Option<&Answer>) -> Self {
+        match (actual, expected) {
+            (Err(_), _) => VerifyStatus::Error,
+            (_, None) => VerifyStatus::MissingExpected,
+            (Ok(a), Some(e)) if a == e => VerifyStatus::Pass,
+            (Ok(_), Some(_)) => VerifyStatus::Fail,
+        }
+    }
+}
+
+impl fmt::Display for VerifyStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyStatus::Pass => write!(f, "pass"),
+            VerifyStatus::Fail => write!(f, "FAIL"),
+            VerifyStatus::Error => write!(f, "ERROR"),
+            VerifyStatus::MissingExpected => write!(f, "missing"),
+            VerifyStatus::Panic => write!(f, "PANIC"),
+            VerifyStatus::Timeout => write!(f, "TIMEOUT"),
+        }
+    }
+}
+
+/// The outcome of a single part of an input, reported by `verify` and `run-all`.
+pub struct PartResult {
+    pub year: u16,
+    /// The directory below the year the input was found in, `None` for `run-all`.
+    pub user: Option<String>,
+    pub day: u8,
+    pub part: PuzzlePart,
+    pub actual: Result<Answer, SolveError>,
+    pub expected: Option<Answer>,
+    pub status: VerifyStatus,
+    /// Time spent in the part itself, without reading and parsing the input.
+    pub elapsed: Duration,
+    pub input_path: PathBuf,
+    pub input_hash: String,
+    /// The answer was taken from the cache, `elapsed` is zero then.
+    pub cached: bool,
+}
+
+/// An input read from a data directory together with the expected answers of both parts.
+pub struct ExpectedInput {
+    pub solver: DaySolver,
+    pub user: Option<String>,
+    pub input_path: PathBuf,
+    pub input: Arc<[u8]>,
+    pub expected: [Option<Answer>; 2],
+}
+
+/// Verifies the data of all registered years, which is expected in `<data_root>/<year>`.
+/// Years without a data directory are skipped, the results are in the order of year,
+/// user, day and part. See [`solve_inputs`] for how the inputs are solved.
+pub fn verify_all_years(
+    registry: &AocRegistry,
+    data_root: &Path,
+    jobs: usize,
+    timeout: Option<Duration>,
+    cache: &AnswerCache,
+) -> io::Result<Vec<PartResult>> {
+    let mut inputs = vec![];
+
+    for solvers in registry.years() {
+        let data_dir = data_root.join(solvers.year().to_string());
+
+        if data_dir.is_dir() {
+            inputs.extend(find_year_inputs(solvers, &data_dir)?);
+        }
+    }
+
+    Ok(solve_inputs(&inputs, jobs, timeout, cache).0)
+}
+
+/// Solves both parts of every input and compares them with the expected answers. Every
+/// input is parsed once and solved as its own job on up to `jobs` threads, the results
+/// are in the order of the inputs and parts. Parts that panic or run longer than `timeout`
+/// don't stop the other parts. A timed out part keeps its thread busy until the process
+/// exits, see [`InputJob::run`]. Answers found in `cache` aren't solved again.
+///
+/// Also returns the total time spent parsing.
+pub fn solve_inputs(
+    inputs: &[ExpectedInput],
+    jobs: usize,
+    timeout: Option<Duration>,
+    cache: &AnswerCache,
+) -> (Vec<PartResult>, Duration) {
+    let input_jobs = inputs
+        .iter()
+        .map(|i| InputJob {
+            solver: i.solver.clone(),
+            parts: PuzzlePart::BOTH.to_vec(),
+            input: i.input.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    let outcomes = jobs::run_ordered(&input_jobs, jobs, |j| cache.run(j, timeout));
+
+    let mut results = vec![];
+    let mut parse_elapsed = Duration::ZERO;
+
+    for (input, outcome) in inputs.iter().zip(outcomes) {
+        let input_hash = common::input_hash(&input.input);
+        parse_elapsed += outcome.parse_elapsed;
+
+        let parts = PuzzlePart::BOTH.into_iter().zip(input.expected.clone());
+
+        for ((part, expected), outcome) in parts.zip(outcome.parts) {
+            results.push(PartResult {
+                year: input.solver.year(),
+                user: input.user.clone(),
+                day: input.solver.day(),
+                part,
+                status: VerifyStatus::of(&outcome, expected.as_ref()),
+                actual: outcome.actual,
+                expected,
+                elapsed: outcome.elapsed,
+                input_path: input.input_path.clone(),
+                input_hash: input_hash.clone(),
+                cached: outcome.cached,
+            });
+        }
+    }
+
+    (results, parse_elapsed)
+}
+
+/// Reads every `<user>/NN-input.txt` below `data_dir` with a registered solver together
+/// with the lines of the matching `NN-output.txt`.
+fn find_year_inputs(
+    solvers: &YearSolverCollection,
+    data_dir: &Path,
+) -> io::Result<Vec<ExpectedInput>> {
+    let mut inputs = vec![];
+
+    for user_dir in common::sorted_entries(data_dir)? {
+        if !user_dir.is_dir() {
+            continue;
+        }
+
+        let user = common::file_name(&user_dir);
+
+        for (day, input_path) in find_inputs(&user_dir)? {
+            let solver = match solvers.solver(day) {
+                Ok(s) => s.clone(),
+                Err(_) => continue,
+            };
+
+            inputs.push(ExpectedInput {
+                solver,
+                user: Some(user.clone()),
+                input: fs::read(&input_path)?.into(),
+                expected: read_expected(&user_dir.join(format!("{day:02}-output.txt")))?,
+                input_path,
+            });
+        }
+    }
+
+    Ok(inputs)
+}
+
+/// The `verify` table with a line per part, followed by the counts of each status.
+pub fn format_table(results: &[PartResult]) -> String {
+    let user_width = results
+        .iter()
+        .map(|r| r.user.as_deref().unwrap_or_default().len())
+        .chain([4])
+        .max()
+        .unwrap_or_default();
+
+    let mut table = format!(
+        "year  {:<user_width$}  day  part  status    answer\n",
+        "user"
+    );
+
+    for r in results {
+        let part = r.part.number();
+
+        let answer = match (&r.actual, &r.expected) {
+            (Err(e), _) => e.to_string(),
+            (Ok(a), Some(e)) if r.status == VerifyStatus::Fail => {
+                format!("{} (expected {})", a.single_line(), e.single_line())
+            }
+            (Ok(a), _) => a.single_line(),
+        };
+
+        table.push_str(&format!(
+            "{}  {:<user_width$}  {:>3}  {:>4}  {:<8}  {}\n",
+            r.year,
+            r.user.as_deref().unwrap_or_default(),
+            r.day,
+            part,
+            r.status.to_string(),
+            answer
+        ));
+    }
+
+    let count = |s| results.iter().filter(|r| r.status == s).count();
+
+    table.push_str(&format!(
+        "{} passed, {} failed, {} errors, {} panics, {} timeouts, {} missing expected\n",
+        count(VerifyStatus::Pass),
+        count(VerifyStatus::Fail),
+        count(VerifyStatus::Error),
+        count(VerifyStatus::Panic),
+        count(VerifyStatus::Timeout),
+        count(VerifyStatus::MissingExpected)
+    ));
+
+    table
+}
+
+/// Reads the expected answers for both parts. Missing files or lines are `None`.
+pub fn read_expected(path: &Path) -> io::Result<[Option<Answer>; 2]> {
+    let content = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok([None, None]),
+        Err(e) => return Err(e),
+    };
+
+    Ok(parse_expected(&content))
+}
+
+/// Sets the expected answer of `part` in an `NN-output.txt`, keeping the other line.
+pub fn write_expected(path: &Path, part: PuzzlePart, answer: &Answer) -> io::Result<()> {
+    let content = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+
+    let mut lines = content
+        .lines()
+        .map(|l| l.trim_end_matches('\r').to_string())
+        .collect::<Vec<_>>();
+
+    let index = match part {
+        PuzzlePart::Part1 => 0,
+        PuzzlePart::Part2 => 1,
+    };
+
+    if lines.len() <= index {
+        lines.resize(index + 1, String::new());
+    }
+
+    lines[index] = answer.to_string();
+    fs::write(path, lines.join("\n") + "\n")
+}
+
+fn parse_expected(content: &str) -> [Option<Answer>; 2] {
+    let mut lines = content
+        .lines()
+        .map(|l| l.trim_end_matches('\r'))
+        .map(|l| l.parse::<Answer>().ok().filter(|_| !l.is_empty()));
+
+    [lines.next().flatten(), lines.next().flatten()]
+}
+
+fn find_inputs(user_dir: &Path) -> io::Result<Vec<(u8, PathBuf)>> {
+    let mut inputs = vec![];
+
+    for path in common::sorted_entries(user_dir)? {
+        let name = common::file_name(&path);
+
+        if let Some(day) = name.strip_suffix("-input.txt") {
+            if let Ok(day) = day.parse::<u8>() {
+                inputs.push((day, path));
+            }
+        }
+    }
+
+    inputs.sort_by_key(|x| x.0);
+    Ok(inputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, time::Duration};
+
+    use super::{format_table, parse_expected, PartResult, VerifyStatus};
+    use crate::aoc_general::{Answer, PuzzlePart};
+
+    #[test]
+    fn expected_output_has_one_line_per_part() {
+        let observed = parse_expected("24000\r\nGMPMLWNMG\n");
+        assert_eq!(
+            [
+                Some(Answer::from(24000u32)),
+                Some(Answer::from("GMPMLWNMG"))
+            ],
+            observed
+        );
+    }
+
+    #[test]
+    fn missing_lines_in_expected_output_are_none() {
+        assert_eq!([Some(Answer::from(2u32)), None], parse_expected("2\n"));
+        assert_eq!([None, Some(Answer::from(4u32))], parse_expected("\n4\n"));
+        assert_eq!([None, None], parse_expected(""));
+    }
+
+    #[test]
+    fn grid_answers_take_a_single_row_of_the_table() {
+        let result = PartResult {
+            year: 2022,
+            user: Some("someone".into()),
+            day: 10,
+            part: PuzzlePart::Part2,
+            actual: Ok(Answer::Grid(vec!["#.".into(), ".#".into()])),
+            expected: Some(Answer::from("EHZFZHCZ")),
+            status: VerifyStatus::Fail,
+            elapsed: Duration::ZERO,
+            input_path: PathBuf::from("10-input.txt"),
+            input_hash: String::new(),
+            cached: false,
+        };
+
+        let table = format_table(&[result]);
+        let rows = table.lines().collect::<Vec<_>>();
+
+        assert_eq!(3, rows.len());
+        assert!(
+            rows[1].ends_with("FAIL      #. / .# (expected EHZFZHCZ)"),
+            "{}",
+            rows[1]
+        );
+    }
+}
@@ -1,38 +1,88 @@
 use crate::{
-    aoc_general::{PuzzlePart, PuzzleSolver},
-    common::{LineSplittable, NormalizeLineBreaks},
+    aoc_general::{Answer, PuzzleSolver, SolveError},
+    common::{parse, SliceLineSplittable},
 };
 
 #[derive(Default)]
 pub struct Day1;
 
 impl PuzzleSolver for Day1 {
-    fn solve(&self, input: &mut dyn Iterator<Item = u8>, part: PuzzlePart) -> String {
-        let lines = input.normalize_line_breaks().split_lf_line_breaks();
-        let mut calories = vec![];
-        let mut tmp_calories_sum = 0;
+    fn year(&self) -> u16 {
+        2022
+    }
+
+    fn day(&self) -> u8 {
+        1
+    }
+
+    /// 2: the last elf is counted without a trailing line break.
+    fn version(&self) -> u32 {
+        2
+    }
 
-        for line in lines {
-            let line = std::str::from_utf8(&line).expect("line should be valid UTF-8");
+    /// Calories carried by each elf, sorted in descending order.
+    type Model = Vec<u64>;
 
+    fn parse(&self, input: &[u8]) -> Result<Self::Model, SolveError> {
+        let lines = input.split_line_breaks();
+        let mut calories = vec![];
+        // `None` between two elves, so repeated empty lines don't add elves without food
+        let mut current_elf: Option<u64> = None;
+
+        for (line_index, line) in lines.enumerate() {
             if line.is_empty() {
-                calories.push(tmp_calories_sum);
-                tmp_calories_sum = 0;
+                calories.extend(current_elf.take());
                 continue;
             }
 
-            let food_calories = line
-                .parse::<u32>()
-                .expect("Food calories not a valid number.");
+            let food_calories = parse::<u32>(line).map_err(|e| e.at_line(line_index + 1))?;
+
+            let sum = current_elf
+                .unwrap_or_default()
+                .checked_add(food_calories.into())
+                .ok_or_else(|| {
+                    SolveError::new("calories of a single elf overflow").at_line(line_index + 1)
+                })?;
 
-            tmp_calories_sum += food_calories;
+            current_elf = Some(sum);
         }
 
-        calories.sort_by(|a, b| b.cmp(a));
+        // the last elf isn't followed by an empty line if the input doesn't end with a line break
+        calories.extend(current_elf);
 
-        match part {
-            PuzzlePart::Part1 => calories[0].to_string(),
-            PuzzlePart::Part2 => calories.iter().take(3).sum::<u32>().to_string(),
+        if calories.is_empty() {
+            return Err(SolveError::new("input should contain at least one elf"));
         }
+
+        calories.sort_by(|a, b| b.cmp(a));
+
+        Ok(calories)
+    }
+
+    fn part1(&self, calories: &Self::Model) -> Result<Answer, SolveError> {
+        Ok(calories[0].into())
+    }
+
+    fn part2(&self, calories: &Self::Model) -> Result<Answer, SolveError> {
+        Ok(calories.iter().take(3).sum::<u64>().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day1;
+    use crate::aoc_general::PuzzleSolver;
+
+    #[test]
+    fn the_last_elf_counts_without_a_trailing_line_break() {
+        assert_eq!(Ok(vec![3000]), Day1.parse(b"1000\n2000"));
+        assert_eq!(Ok(vec![3000, 5]), Day1.parse(b"5\n\n\n1000\n2000\n"));
+        assert!(Day1.parse(b"\n\n").is_err());
+    }
+
+    #[test]
+    fn large_calories_do_not_overflow() {
+        let input = format!("{}\n{}\n", u32::MAX, u32::MAX);
+        assert_eq!(Ok(vec![2 * u32::MAX as u64]), Day1.parse(input.as_bytes()));
     }
 }
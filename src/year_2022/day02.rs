@@ -1,61 +1,97 @@
 use crate::{
-    aoc_general::{PuzzlePart, PuzzleSolver},
-    common::{LfEofDropable, LineSplittable, NormalizeLineBreaks},
+    aoc_general::{Answer, PuzzleSolver, SolveError},
+    common::{LfEofDropable, SliceLineSplittable},
 };
 
 #[derive(Default)]
 pub struct Day2;
 
 impl PuzzleSolver for Day2 {
-    fn solve(&self, input: &mut dyn Iterator<Item = u8>, part: PuzzlePart) -> String {
-        let lines = input
-            .normalize_line_breaks()
-            .split_lf_line_breaks()
-            .drop_lf_eof();
-
-        let mut score = 0;
-
-        for line in lines {
-            assert!(line.len() == 3);
-            assert!(line[1] == b' ');
-
-            let first_code = line[0];
-            let second_code = line[2];
-
-            let opponent_gesture = HandGesture::parse_opponent_gesture(first_code);
-
-            let (mine, outcome) = match part {
-                PuzzlePart::Part1 => {
-                    let g = HandGesture::parse_my_gesture_part1(second_code);
-                    let o = g.play(opponent_gesture);
-
-                    (g, o)
-                }
-                PuzzlePart::Part2 => {
-                    let o = GameOutcome::parse_outcome_part2(second_code);
-                    let g = HandGesture::get_my_shape(opponent_gesture, o);
-
-                    (g, o)
-                }
-            };
-
-            let shape_score = match mine {
-                HandGesture::Rock => 1,
-                HandGesture::Paper => 2,
-                HandGesture::Scissors => 3,
-            };
-
-            let outcome_score = match outcome {
-                GameOutcome::Lose => 0,
-                GameOutcome::Draw => 3,
-                GameOutcome::Win => 6,
-            };
-
-            score += outcome_score + shape_score
+    fn year(&self) -> u16 {
+        2022
+    }
+
+    fn day(&self) -> u8 {
+        2
+    }
+
+    type Model = Vec<Round>;
+
+    fn parse(&self, input: &[u8]) -> Result<Self::Model, SolveError> {
+        let lines = input.split_line_breaks().drop_lf_eof();
+
+        let mut rounds = vec![];
+
+        for (line_index, line) in lines.enumerate() {
+            let line_number = line_index + 1;
+
+            if line.len() != 3 || line[1] != b' ' {
+                return Err(
+                    SolveError::new("expected two codes separated by a space").at_line(line_number)
+                );
+            }
+
+            let opponent = HandGesture::parse_opponent_gesture(line[0])
+                .map_err(|e| e.at_line(line_number).at_column(1))?;
+
+            rounds.push(Round {
+                line_number,
+                opponent,
+                second_code: line[2],
+            });
         }
 
-        score.to_string()
+        Ok(rounds)
     }
+
+    fn part1(&self, rounds: &Self::Model) -> Result<Answer, SolveError> {
+        total_score(rounds, |round| {
+            let g = HandGesture::parse_my_gesture_part1(round.second_code)?;
+            Ok((g, g.play(round.opponent)))
+        })
+    }
+
+    fn part2(&self, rounds: &Self::Model) -> Result<Answer, SolveError> {
+        total_score(rounds, |round| {
+            let o = GameOutcome::parse_outcome_part2(round.second_code)?;
+            Ok((HandGesture::get_my_shape(round.opponent, o), o))
+        })
+    }
+}
+
+/// A single line of the strategy guide. The meaning of the second code depends on the part.
+pub struct Round {
+    line_number: usize,
+    opponent: HandGesture,
+    second_code: u8,
+}
+
+fn total_score<F>(rounds: &[Round], decode: F) -> Result<Answer, SolveError>
+where
+    F: Fn(&Round) -> Result<(HandGesture, GameOutcome), SolveError>,
+{
+    let mut score = 0;
+
+    for round in rounds {
+        let (mine, outcome) =
+            decode(round).map_err(|e| e.at_line(round.line_number).at_column(3))?;
+
+        let shape_score = match mine {
+            HandGesture::Rock => 1,
+            HandGesture::Paper => 2,
+            HandGesture::Scissors => 3,
+        };
+
+        let outcome_score = match outcome {
+            GameOutcome::Lose => 0,
+            GameOutcome::Draw => 3,
+            GameOutcome::Win => 6,
+        };
+
+        score += outcome_score + shape_score
+    }
+
+    Ok(score.into())
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -73,21 +109,27 @@ enum GameOutcome {
 }
 
 impl HandGesture {
-    pub fn parse_opponent_gesture(c: u8) -> Self {
+    pub fn parse_opponent_gesture(c: u8) -> Result<Self, SolveError> {
         match c {
-            b'A' => Self::Rock,
-            b'B' => Self::Paper,
-            b'C' => Self::Scissors,
-            _ => panic!("Invalid code for opponent shape: '{c}'."),
+            b'A' => Ok(Self::Rock),
+            b'B' => Ok(Self::Paper),
+            b'C' => Ok(Self::Scissors),
+            _ => Err(SolveError::new(format!(
+                "Invalid code for opponent shape: '{}'.",
+                c as char
+            ))),
         }
     }
 
-    pub fn parse_my_gesture_part1(c: u8) -> Self {
+    pub fn parse_my_gesture_part1(c: u8) -> Result<Self, SolveError> {
         match c {
-            b'X' => Self::Rock,
-            b'Y' => Self::Paper,
-            b'Z' => Self::Scissors,
-            _ => panic!("Invalid code for my shape: '{c}'."),
+            b'X' => Ok(Self::Rock),
+            b'Y' => Ok(Self::Paper),
+            b'Z' => Ok(Self::Scissors),
+            _ => Err(SolveError::new(format!(
+                "Invalid code for my shape: '{}'.",
+                c as char
+            ))),
         }
     }
 
@@ -129,12 +171,15 @@ impl HandGesture {
 }
 
 impl GameOutcome {
-    pub fn parse_outcome_part2(c: u8) -> Self {
+    pub fn parse_outcome_part2(c: u8) -> Result<Self, SolveError> {
         match c {
-            b'X' => Self::Lose,
-            b'Y' => Self::Draw,
-            b'Z' => Self::Win,
-            _ => panic!("Invalid code for outcome: '{c}'."),
+            b'X' => Ok(Self::Lose),
+            b'Y' => Ok(Self::Draw),
+            b'Z' => Ok(Self::Win),
+            _ => Err(SolveError::new(format!(
+                "Invalid code for outcome: '{}'.",
+                c as char
+            ))),
         }
     }
 }
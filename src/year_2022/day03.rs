@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use itertools::Itertools;
 
 use crate::{
-    aoc_general::{PuzzlePart, PuzzleSolver},
+    aoc_general::{Answer, PuzzleSolver, SolveError},
     common::{LfEofDropable, LineSplittable, NormalizeLineBreaks},
 };
 
@@ -11,75 +11,111 @@ use crate::{
 pub struct Day3;
 
 impl PuzzleSolver for Day3 {
-    fn solve(&self, input: &mut dyn Iterator<Item = u8>, part: PuzzlePart) -> String {
-        let mut lines = input
+    fn year(&self) -> u16 {
+        2022
+    }
+
+    fn day(&self) -> u8 {
+        3
+    }
+
+    /// The items of each rucksack together with its line number.
+    type Model = Vec<(usize, Vec<u8>)>;
+
+    fn parse(&self, input: &[u8]) -> Result<Self::Model, SolveError> {
+        // the model keeps owned lines anyway, so there is nothing to gain from borrowing them
+        Ok(input
+            .iter()
+            .copied()
             .normalize_line_breaks()
             .split_lf_line_breaks()
-            .drop_lf_eof();
+            .drop_lf_eof()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line))
+            .collect())
+    }
 
-        match part {
-            PuzzlePart::Part1 => day03_part1(&mut lines),
-            PuzzlePart::Part2 => day03_part2(&mut lines),
-        }
+    fn part1(&self, rucksacks: &Self::Model) -> Result<Answer, SolveError> {
+        day03_part1(rucksacks)
+    }
+
+    fn part2(&self, rucksacks: &Self::Model) -> Result<Answer, SolveError> {
+        day03_part2(rucksacks)
     }
 }
 
-fn get_priority_for_item(item: u8) -> u8 {
+fn get_priority_for_item(item: u8) -> Result<u8, SolveError> {
     match item {
-        (b'a'..=b'z') => item - b'a' + 1,
-        (b'A'..=b'Z') => item - b'A' + 27,
-        _ => panic!("Unexpected item '{}'", item as char),
+        (b'a'..=b'z') => Ok(item - b'a' + 1),
+        (b'A'..=b'Z') => Ok(item - b'A' + 27),
+        _ => Err(SolveError::new(format!(
+            "Unexpected item '{}'",
+            item as char
+        ))),
     }
 }
 
-fn day03_part1<I>(lines: &mut I) -> String
-where
-    I: Iterator<Item = Vec<u8>>,
-{
-    lines
-        .map(|x| {
-            let len = x.len();
+fn day03_part1(lines: &[(usize, Vec<u8>)]) -> Result<Answer, SolveError> {
+    let mut sum = 0;
+
+    for &(line_number, ref x) in lines {
+        let len = x.len();
 
-            assert!(len % 2 == 0);
-            assert!(len >= 2);
+        if len % 2 != 0 || len < 2 {
+            return Err(SolveError::new(
+                "rucksack should contain a non-zero, even number of items",
+            )
+            .at_line(line_number));
+        }
 
-            let half = len / 2;
+        let half = len / 2;
 
-            let compartment1 = &x[0..half];
-            let compartment2 = &x[half..];
+        let compartment1 = &x[0..half];
+        let compartment2 = &x[half..];
 
-            let positions =
-                find_duplicate(compartment1, compartment2).expect("No duplicate item found");
+        let positions = find_duplicate(compartment1, compartment2)
+            .ok_or_else(|| SolveError::new("No duplicate item found").at_line(line_number))?;
 
-            let item = &compartment1[positions.0];
+        let item = &compartment1[positions.0];
 
-            i32::from(get_priority_for_item(*item))
-        })
-        .sum::<i32>()
-        .to_string()
+        sum += i32::from(
+            get_priority_for_item(*item)
+                .map_err(|e| e.at_line(line_number).at_column(positions.0 + 1))?,
+        );
+    }
+
+    Ok(sum.into())
 }
 
-fn day03_part2<I>(lines: &mut I) -> String
-where
-    I: Iterator<Item = Vec<u8>>,
-{
-    lines
-        .chunks(3)
-        .into_iter()
-        .map(|group| {
-            let mut map = HashMap::new();
-
-            for group_member in group {
-                for b in group_member.iter().unique() {
-                    map.entry(*b).and_modify(|x| *x += 1).or_insert(1);
-                }
+fn day03_part2(lines: &[(usize, Vec<u8>)]) -> Result<Answer, SolveError> {
+    let mut sum = 0;
+
+    for group in lines.chunks(3) {
+        let mut map = HashMap::new();
+        let mut first_line_number = None;
+
+        for &(line_number, ref group_member) in group {
+            first_line_number.get_or_insert(line_number);
+
+            for b in group_member.iter().unique() {
+                map.entry(*b).and_modify(|x| *x += 1).or_insert(1);
             }
+        }
+
+        let first_line_number = first_line_number.unwrap_or_default();
+
+        let item = *map
+            .iter()
+            .find(|x| *x.1 == 3)
+            .ok_or_else(|| {
+                SolveError::new("group of three has no common item").at_line(first_line_number)
+            })?
+            .0;
+
+        sum += i32::from(get_priority_for_item(item).map_err(|e| e.at_line(first_line_number))?);
+    }
 
-            let item = *map.iter().find(|x| *x.1 == 3).unwrap().0;
-            i32::from(get_priority_for_item(item))
-        })
-        .sum::<i32>()
-        .to_string()
+    Ok(sum.into())
 }
 
 fn find_duplicate<T>(a: &[T], b: &[T]) -> Option<(usize, usize)>
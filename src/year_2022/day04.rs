@@ -1,59 +1,64 @@
 use crate::{
-    aoc_general::{PuzzlePart, PuzzleSolver},
-    common::{parse, LfEofDropable, LineSplittable, NormalizeLineBreaks},
+    aoc_general::{Answer, PuzzleSolver, SolveError},
+    common::Scanner,
 };
 
 #[derive(Default)]
 pub struct Day4;
 
 impl PuzzleSolver for Day4 {
-    fn solve(&self, input: &mut dyn Iterator<Item = u8>, part: PuzzlePart) -> String {
-        let lines = input
-            .normalize_line_breaks()
-            .split_lf_line_breaks()
-            .drop_lf_eof();
-
-        let mut count = 0;
-
-        for line in lines {
-            let (a, b) = parse_double_range(&line);
-
-            if match part {
-                PuzzlePart::Part1 => Range::one_contains_other(&a, &b),
-                PuzzlePart::Part2 => a.overlaps_with(&b),
-            } {
-                count += 1;
-            }
+    fn year(&self) -> u16 {
+        2022
+    }
+
+    fn day(&self) -> u8 {
+        4
+    }
+
+    type Model = Vec<(Range, Range)>;
+
+    fn parse(&self, input: &[u8]) -> Result<Self::Model, SolveError> {
+        let mut scanner = Scanner::new(input);
+        let mut pairs = vec![];
+
+        while !scanner.is_eof() {
+            let a = parse_range(&mut scanner)?;
+            scanner.expect_literal(b",")?;
+            let b = parse_range(&mut scanner)?;
+            scanner.expect_line_end()?;
+
+            pairs.push((a, b));
         }
 
-        count.to_string()
+        Ok(pairs)
     }
-}
 
-fn parse_double_range(data: &[u8]) -> (Range, Range) {
-    let (a, b) = split(b',', data);
-    (parse_range(a), parse_range(b))
-}
+    fn part1(&self, pairs: &Self::Model) -> Result<Answer, SolveError> {
+        let count = pairs
+            .iter()
+            .filter(|(a, b)| Range::one_contains_other(a, b))
+            .count();
+
+        Ok(count.into())
+    }
 
-fn parse_range(data: &[u8]) -> Range {
-    let (a, b) = split(b'-', data);
+    fn part2(&self, pairs: &Self::Model) -> Result<Answer, SolveError> {
+        let count = pairs.iter().filter(|(a, b)| a.overlaps_with(b)).count();
 
-    Range {
-        start: parse(a),
-        end: parse(b),
+        Ok(count.into())
     }
 }
 
-fn split(delimiter: u8, data: &[u8]) -> (&[u8], &[u8]) {
-    let index = data
-        .iter()
-        .position(|&x| x == delimiter)
-        .expect("data should have delimiter at least once");
+/// Parses `start-end`.
+fn parse_range(scanner: &mut Scanner) -> Result<Range, SolveError> {
+    let start = scanner.read_uint()?;
+    scanner.expect_literal(b"-")?;
+    let end = scanner.read_uint()?;
 
-    (&data[0..index], &data[index + 1..])
+    Ok(Range { start, end })
 }
 
-struct Range {
+pub struct Range {
     pub start: i32,
     pub end: i32,
 }
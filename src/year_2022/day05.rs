@@ -1,17 +1,28 @@
 use crate::{
-    aoc_general::{PuzzlePart, PuzzleSolver},
-    common::{parse, LfEofDropable, LineSplittable, NormalizeLineBreaks},
+    aoc_general::{Answer, PuzzlePart, PuzzleSolver, SolveError},
+    common::{LfEofDropable, Scanner, SliceLineSplittable},
 };
 
 #[derive(Default)]
 pub struct Day5;
 
 impl PuzzleSolver for Day5 {
-    fn solve(&self, input: &mut dyn Iterator<Item = u8>, part: PuzzlePart) -> String {
+    fn year(&self) -> u16 {
+        2022
+    }
+
+    fn day(&self) -> u8 {
+        5
+    }
+
+    type Model = Cargo;
+
+    fn parse(&self, input: &[u8]) -> Result<Self::Model, SolveError> {
         let mut lines = input
-            .normalize_line_breaks()
-            .split_lf_line_breaks()
+            .split_line_breaks()
             .drop_lf_eof()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line))
             .peekable();
 
         // We use the fact that the input at the top is always padded with spaces to be a constant width.
@@ -20,11 +31,18 @@ impl PuzzleSolver for Day5 {
 
         let expanded_width = lines
             .peek()
-            .expect("input should at least have a single line")
+            .ok_or_else(|| SolveError::new("input should at least have a single line"))?
+            .1
             .len()
             + 1;
 
-        assert!(expanded_width % 4 == 0);
+        if !expanded_width.is_multiple_of(4) {
+            return Err(SolveError::new(
+                "stack lines should be padded to a width of 4 * columns - 1",
+            )
+            .at_line(1));
+        }
+
         let column_count = expanded_width / 4;
 
         let mut columns = Vec::<Vec<u8>>::with_capacity(column_count);
@@ -36,7 +54,17 @@ impl PuzzleSolver for Day5 {
 
         // initialize stacks
         loop {
-            let line = lines.next().expect("line should contain stack data");
+            let (line_number, line) = lines
+                .next()
+                .ok_or_else(|| SolveError::new("input ended before the column numbers"))?;
+
+            if line.len() + 1 < expanded_width {
+                return Err(SolveError::new(format!(
+                    "stack line should have a width of {}",
+                    expanded_width - 1
+                ))
+                .at_line(line_number));
+            }
 
             if line[1] == b'1' && line[0] == b' ' {
                 // found line with column numbers, so break
@@ -57,54 +85,131 @@ impl PuzzleSolver for Day5 {
         // reverse vectors so the top ones (we added first) are at the end of the list
         columns.iter_mut().for_each(|c| c.reverse());
 
-        // skip and assert empty line
-        let line = lines.next().unwrap();
-        assert!(line.is_empty());
+        // skip empty line
+        match lines.next() {
+            Some((_, [])) => {}
+            Some((line_number, _)) => {
+                return Err(
+                    SolveError::new("expected an empty line after the column numbers")
+                        .at_line(line_number),
+                )
+            }
+            None => return Err(SolveError::new("input ended before the move instructions")),
+        }
 
-        let mut temp_column = vec![];
+        let mut moves = vec![];
 
-        // apply moves to current state
-        loop {
-            let line = lines.next();
+        for (line_number, line) in lines {
+            let (count, source, target) = parse_move(line, line_number, column_count)?;
 
-            if line.is_none() {
-                // found end of file/input
-                break;
-            }
+            moves.push(Move {
+                line_number,
+                count,
+                source,
+                target,
+            });
+        }
+
+        Ok(Cargo { columns, moves })
+    }
+
+    fn part1(&self, cargo: &Self::Model) -> Result<Answer, SolveError> {
+        cargo.rearrange(PuzzlePart::Part1)
+    }
 
-            // destruct move instruction
-            let line = line.unwrap();
-            let mut parts = line.split(|x| *x == b' ');
-            assert!(parts.next().unwrap() == b"move");
-            let count = parse::<i32>(parts.next().unwrap());
-            assert!(parts.next().unwrap() == b"from");
-            let source = parse::<usize>(parts.next().unwrap()) - 1;
-            assert!(parts.next().unwrap() == b"to");
-            let target = parse::<usize>(parts.next().unwrap()) - 1;
-
-            match part {
+    fn part2(&self, cargo: &Self::Model) -> Result<Answer, SolveError> {
+        cargo.rearrange(PuzzlePart::Part2)
+    }
+}
+
+/// The initial stacks (top crate at the end of each column) and the moves to apply.
+pub struct Cargo {
+    columns: Vec<Vec<u8>>,
+    moves: Vec<Move>,
+}
+
+struct Move {
+    line_number: usize,
+    count: i32,
+    source: usize,
+    target: usize,
+}
+
+impl Cargo {
+    /// Applies all moves to a copy of the stacks and returns the crates on top.
+    /// The crane of part 1 moves one crate at a time, the one of part 2 moves all at once.
+    fn rearrange(&self, part: PuzzlePart) -> Result<Answer, SolveError> {
+        let mut columns = self.columns.clone();
+        let mut temp_column = vec![];
+
+        for m in &self.moves {
+            let (count, source, target) = (m.count, m.source, m.target);
+
+            let result = match part {
                 PuzzlePart::Part1 => {
                     let (source, target) = borrow_2_mut(&mut columns, source, target);
-                    move_crates(source, target, count);
-                }
-                PuzzlePart::Part2 => {
-                    move_crates(&mut columns[source], &mut temp_column, count);
-                    move_crates(&mut temp_column, &mut columns[target], count);
+                    move_crates(source, target, count)
                 }
-            }
+                PuzzlePart::Part2 => move_crates(&mut columns[source], &mut temp_column, count)
+                    .and_then(|_| move_crates(&mut temp_column, &mut columns[target], count)),
+            };
+
+            result.map_err(|e| e.at_line(m.line_number))?;
         }
 
-        let output: Vec<_> = columns.iter().map(|c| *c.last().unwrap()).collect();
+        let output = columns
+            .iter()
+            .map(|c| c.last().copied())
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| SolveError::new("every stack should have a crate on top at the end"))?;
 
-        String::from_utf8(output).unwrap()
+        Ok(Answer::Text(String::from_utf8_lossy(&output).to_string()))
     }
 }
 
-fn move_crates(source: &mut Vec<u8>, target: &mut Vec<u8>, amount: i32) {
+/// Destructs a `move N from A to B` instruction into the count and zero based column indices.
+fn parse_move(
+    line: &[u8],
+    line_number: usize,
+    column_count: usize,
+) -> Result<(i32, usize, usize), SolveError> {
+    let mut scanner = Scanner::for_line(line, line_number);
+
+    let read_column = |scanner: &mut Scanner| {
+        let start = scanner.position();
+
+        scanner
+            .read_uint::<usize>()
+            .ok()
+            .filter(|x| (1..=column_count).contains(x))
+            .map(|x| x - 1)
+            .ok_or_else(|| scanner.error_at(start, "invalid column number"))
+    };
+
+    scanner.expect_literal(b"move ")?;
+    let count = scanner.read_uint::<i32>()?;
+    scanner.expect_literal(b" from ")?;
+    let source = read_column(&mut scanner)?;
+    scanner.expect_literal(b" to ")?;
+    let target = read_column(&mut scanner)?;
+    scanner.expect_line_end()?;
+
+    if source == target {
+        return Err(SolveError::new("source and target column should differ").at_line(line_number));
+    }
+
+    Ok((count, source, target))
+}
+
+fn move_crates(source: &mut Vec<u8>, target: &mut Vec<u8>, amount: i32) -> Result<(), SolveError> {
     for _ in 0..amount {
-        let item = source.pop().unwrap();
+        let item = source
+            .pop()
+            .ok_or_else(|| SolveError::new("can't move a crate from an empty stack"))?;
         target.push(item);
     }
+
+    Ok(())
 }
 
 fn borrow_2_mut<T>(slice: &mut [T], a: usize, b: usize) -> (&mut T, &mut T) {
@@ -1,34 +1,56 @@
-use crate::aoc_general::{PuzzlePart, PuzzleSolver};
+use crate::aoc_general::{Answer, PuzzleSolver, SolveError};
 
 #[derive(Default)]
 pub struct Day6;
 
 impl PuzzleSolver for Day6 {
-    fn solve(&self, input: &mut dyn Iterator<Item = u8>, part: PuzzlePart) -> String {
-        let window_size = match part {
-            PuzzlePart::Part1 => 4,
-            PuzzlePart::Part2 => 14,
-        };
-
-        let mut window = (0..window_size)
-            .into_iter()
-            .map(|_| {
-                input
-                    .next()
-                    .expect("input should have at least the same length as the window size.")
-            })
-            .collect::<Vec<_>>();
-
-        let mut index = window_size;
-
-        loop {
-            if all_chars_different(&window) {
-                return index.to_string();
-            }
+    fn year(&self) -> u16 {
+        2022
+    }
+
+    fn day(&self) -> u8 {
+        6
+    }
+
+    /// The raw datastream buffer.
+    type Model = Vec<u8>;
+
+    fn parse(&self, input: &[u8]) -> Result<Self::Model, SolveError> {
+        Ok(input.to_vec())
+    }
+
+    fn part1(&self, buffer: &Self::Model) -> Result<Answer, SolveError> {
+        find_marker(buffer, 4)
+    }
 
-            window[index % window_size] = input.next().unwrap();
-            index += 1;
+    fn part2(&self, buffer: &Self::Model) -> Result<Answer, SolveError> {
+        find_marker(buffer, 14)
+    }
+}
+
+/// Returns the number of characters processed until the last `window_size` ones were all different.
+fn find_marker(buffer: &[u8], window_size: usize) -> Result<Answer, SolveError> {
+    let mut input = buffer.iter().copied();
+    let mut window = (&mut input).take(window_size).collect::<Vec<_>>();
+
+    if window.len() < window_size {
+        return Err(SolveError::new(
+            "input should have at least the same length as the window size.",
+        ));
+    }
+
+    let mut index = window_size;
+
+    loop {
+        if all_chars_different(&window) {
+            return Ok(index.into());
         }
+
+        window[index % window_size] = input
+            .next()
+            .ok_or_else(|| SolveError::new("input doesn't contain a marker"))?;
+
+        index += 1;
     }
 }
 
@@ -0,0 +1,367 @@
+use std::collections::VecDeque;
+
+/// Index of a node in the arena of a [`FileSystem`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(usize);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeKind {
+    File,
+    Folder { children: Vec<NodeId> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    /// Empty for the root folder.
+    name: String,
+    parent: Option<NodeId>,
+    /// The size of a file or the cumulative size of everything inside a folder.
+    size: u64,
+    kind: NodeKind,
+}
+
+impl Node {
+    /// `None` only for the root folder.
+    pub fn parent(&self) -> Option<NodeId> {
+        self.parent
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn kind(&self) -> &NodeKind {
+        &self.kind
+    }
+
+    pub fn is_folder(&self) -> bool {
+        matches!(self.kind, NodeKind::Folder { .. })
+    }
+
+    /// Empty for files.
+    pub fn children(&self) -> &[NodeId] {
+        match &self.kind {
+            NodeKind::File => &[],
+            NodeKind::Folder { children } => children,
+        }
+    }
+}
+
+/// The folders and files seen in a terminal transcript, stored in a single arena.
+///
+/// Nodes are only ever appended and a child is always created after its parent, so a child has
+/// a higher [`NodeId`] than its parent. That's what makes [`FileSystem::aggregate_sizes`] a
+/// single backwards pass.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSystem {
+    nodes: Vec<Node>,
+    working_dir: NodeId,
+}
+
+impl Default for FileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FileSystem {
+    pub const ROOT: NodeId = NodeId(0);
+
+    pub fn new() -> Self {
+        let root = Node {
+            name: String::new(),
+            parent: None,
+            size: 0,
+            kind: NodeKind::Folder { children: vec![] },
+        };
+
+        FileSystem {
+            nodes: vec![root],
+            working_dir: Self::ROOT,
+        }
+    }
+
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id.0]
+    }
+
+    /// Files and folders including the root, so never zero.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn working_dir(&self) -> NodeId {
+        self.working_dir
+    }
+
+    /// Change into a directory (and create if it doesn't yet exist). `..` at the root stays
+    /// at the root.
+    pub(super) fn cd(&mut self, name: &str) {
+        self.working_dir = match name {
+            "/" => Self::ROOT,
+            ".." => self.node(self.working_dir).parent.unwrap_or(Self::ROOT),
+            _ => self.find_or_create_subdirectory(name),
+        };
+    }
+
+    /// Change into a folder that is already known.
+    pub(super) fn cd_to(&mut self, folder: NodeId) {
+        debug_assert!(self.node(folder).is_folder());
+        self.working_dir = folder;
+    }
+
+    pub(super) fn find_or_create_subdirectory(&mut self, name: &str) -> NodeId {
+        if let Some(id) = self.find_subdirectory(self.working_dir, name) {
+            return id;
+        }
+
+        self.push_child(name, NodeKind::Folder { children: vec![] })
+    }
+
+    pub(super) fn add_file(&mut self, name: &str, size: u64) -> NodeId {
+        let id = self.push_child(name, NodeKind::File);
+        self.nodes[id.0].size = size;
+        id
+    }
+
+    fn push_child(&mut self, name: &str, kind: NodeKind) -> NodeId {
+        let id = NodeId(self.nodes.len());
+
+        self.nodes.push(Node {
+            name: name.to_string(),
+            parent: Some(self.working_dir),
+            size: 0,
+            kind,
+        });
+
+        if let NodeKind::Folder { children, .. } = &mut self.nodes[self.working_dir.0].kind {
+            children.push(id);
+        }
+
+        id
+    }
+
+    /// The first file or folder with that name directly in `folder`.
+    pub fn find_child(&self, folder: NodeId, name: &str) -> Option<NodeId> {
+        self.node(folder)
+            .children()
+            .iter()
+            .copied()
+            .find(|&c| self.node(c).name == name)
+    }
+
+    pub fn find_subdirectory(&self, folder: NodeId, name: &str) -> Option<NodeId> {
+        self.node(folder)
+            .children()
+            .iter()
+            .copied()
+            .find(|&c| self.node(c).is_folder() && self.node(c).name == name)
+    }
+
+    /// The absolute path of a node, `/` for the root and `/a/e` for folder `e` in `a`.
+    pub fn path(&self, id: NodeId) -> String {
+        let mut names = vec![];
+        let mut current = Some(id);
+
+        while let Some(c) = current.filter(|&c| c != Self::ROOT) {
+            names.push(self.node(c).name());
+            current = self.node(c).parent;
+        }
+
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    /// Sets the size of every folder to the sum of its children, without recursion. Has to be
+    /// called after the last file was added.
+    pub(super) fn aggregate_sizes(&mut self) {
+        for node in self.nodes.iter_mut().filter(|n| n.is_folder()) {
+            node.size = 0;
+        }
+
+        for i in (1..self.nodes.len()).rev() {
+            let size = self.nodes[i].size;
+
+            if let Some(parent) = self.nodes[i].parent {
+                self.nodes[parent.0].size += size;
+            }
+        }
+    }
+
+    /// Every folder, in pre-order.
+    pub fn folders(&self) -> impl Iterator<Item = &Node> + '_ {
+        self.pre_order(Self::ROOT)
+            .map(|id| self.node(id))
+            .filter(|n| n.is_folder())
+    }
+
+    /// `start` and everything below it, every folder before its children.
+    pub fn pre_order(&self, start: NodeId) -> PreOrder<'_> {
+        PreOrder {
+            fs: self,
+            stack: vec![start],
+        }
+    }
+
+    /// `start` and everything below it, every folder after its children.
+    pub fn post_order(&self, start: NodeId) -> PostOrder<'_> {
+        PostOrder {
+            fs: self,
+            stack: vec![(start, false)],
+        }
+    }
+
+    /// `start` and everything below it, level by level.
+    pub fn breadth_first(&self, start: NodeId) -> BreadthFirst<'_> {
+        BreadthFirst {
+            fs: self,
+            queue: VecDeque::from([start]),
+        }
+    }
+}
+
+pub struct PreOrder<'a> {
+    fs: &'a FileSystem,
+    stack: Vec<NodeId>,
+}
+
+impl Iterator for PreOrder<'_> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = self.stack.pop()?;
+
+        // reversed so the first child is visited first
+        self.stack
+            .extend(self.fs.node(id).children().iter().rev().copied());
+
+        Some(id)
+    }
+}
+
+pub struct PostOrder<'a> {
+    fs: &'a FileSystem,
+    /// The flag is set once the children of the node are on the stack.
+    stack: Vec<(NodeId, bool)>,
+}
+
+impl Iterator for PostOrder<'_> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (id, expanded) = self.stack.pop()?;
+
+            if expanded {
+                return Some(id);
+            }
+
+            self.stack.push((id, true));
+            self.stack.extend(
+                self.fs
+                    .node(id)
+                    .children()
+                    .iter()
+                    .rev()
+                    .map(|&c| (c, false)),
+            );
+        }
+    }
+}
+
+pub struct BreadthFirst<'a> {
+    fs: &'a FileSystem,
+    queue: VecDeque<NodeId>,
+}
+
+impl Iterator for BreadthFirst<'_> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = self.queue.pop_front()?;
+        self.queue
+            .extend(self.fs.node(id).children().iter().copied());
+
+        Some(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FileSystem, NodeId};
+
+    /// `/` with `a/` (`b` of 10 and `c/` with `d` of 20) and `e` of 5.
+    fn example() -> FileSystem {
+        let mut fs = FileSystem::new();
+        fs.find_or_create_subdirectory("a");
+        fs.add_file("e", 5);
+        fs.cd("a");
+        fs.add_file("b", 10);
+        fs.cd("c");
+        fs.add_file("d", 20);
+        fs.cd("..");
+        fs.cd("..");
+        fs.cd("..");
+        fs.aggregate_sizes();
+        fs
+    }
+
+    #[test]
+    fn folder_sizes_include_everything_below() {
+        let fs = example();
+
+        let sizes = fs.folders().map(|n| n.size()).collect::<Vec<_>>();
+        assert_eq!(vec![35, 30, 20], sizes);
+        assert_eq!(6, fs.node_count());
+        assert_eq!(FileSystem::ROOT, fs.working_dir());
+    }
+
+    #[test]
+    fn paths_are_absolute() {
+        let fs = example();
+
+        assert_eq!("/", fs.path(FileSystem::ROOT));
+        assert_eq!("/e", fs.path(NodeId(2)));
+        assert_eq!("/a/c/d", fs.path(NodeId(5)));
+    }
+
+    #[test]
+    fn traversals_visit_every_node_once() {
+        let fs = example();
+        let ids = |v: &[usize]| v.iter().map(|&i| NodeId(i)).collect::<Vec<_>>();
+
+        assert_eq!(
+            ids(&[0, 1, 3, 4, 5, 2]),
+            fs.pre_order(FileSystem::ROOT).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            ids(&[3, 5, 4, 1, 2, 0]),
+            fs.post_order(FileSystem::ROOT).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            ids(&[0, 1, 2, 3, 4, 5]),
+            fs.breadth_first(FileSystem::ROOT).collect::<Vec<_>>()
+        );
+        assert_eq!(ids(&[4, 5]), fs.pre_order(NodeId(4)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn very_deep_trees_do_not_overflow_the_stack() {
+        let mut fs = FileSystem::new();
+
+        for _ in 0..200_000 {
+            fs.cd("x");
+            fs.add_file("y", 1);
+        }
+
+        fs.aggregate_sizes();
+
+        assert_eq!(200_000, fs.node(FileSystem::ROOT).size());
+        assert_eq!(400_001, fs.post_order(FileSystem::ROOT).count());
+        assert_eq!(Some(FileSystem::ROOT), fs.node(NodeId(1)).parent());
+    }
+}
@@ -0,0 +1,247 @@
+//! `tree` and `du` style listings of a reconstructed filesystem, as text or JSON.
+
+use std::cmp::Reverse;
+
+use super::{FileSystem, Node, NodeId};
+use crate::common::json_string;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SortOrder {
+    /// The order in which the transcript listed the nodes.
+    Transcript,
+    Name,
+    /// Largest first, equal sizes by name.
+    Size,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ListingFormat {
+    Text,
+    Json,
+}
+
+/// Every folder and file below the root, drawn like `tree` or nested as JSON objects.
+pub fn tree(fs: &FileSystem, sort: SortOrder, format: ListingFormat) -> String {
+    match format {
+        ListingFormat::Text => tree_text(fs, sort),
+        ListingFormat::Json => tree_json(fs, sort),
+    }
+}
+
+/// The cumulative size of every folder with its full path, by default like `du` with every
+/// folder after its subfolders.
+pub fn du(fs: &FileSystem, sort: SortOrder, format: ListingFormat) -> String {
+    let mut folders = fs
+        .post_order(FileSystem::ROOT)
+        .filter(|&id| fs.node(id).is_folder())
+        .map(|id| (fs.path(id), fs.node(id).size()))
+        .collect::<Vec<_>>();
+
+    match sort {
+        SortOrder::Transcript => {}
+        SortOrder::Name => folders.sort_unstable(),
+        SortOrder::Size => {
+            folders.sort_unstable_by(|a, b| (Reverse(a.1), &a.0).cmp(&(Reverse(b.1), &b.0)))
+        }
+    }
+
+    match format {
+        ListingFormat::Text => folders
+            .iter()
+            .map(|(path, size)| format!("{}\t{}\n", size, path))
+            .collect(),
+        ListingFormat::Json => {
+            let objects = folders
+                .iter()
+                .map(|(path, size)| format!("{{\"path\":{},\"size\":{}}}", json_string(path), size))
+                .collect::<Vec<_>>();
+
+            format!("[{}]\n", objects.join(","))
+        }
+    }
+}
+
+fn sorted_children(fs: &FileSystem, id: NodeId, sort: SortOrder) -> Vec<NodeId> {
+    let mut children = fs.node(id).children().to_vec();
+    let key = |&c: &NodeId| (fs.node(c).name(), c);
+
+    match sort {
+        SortOrder::Transcript => {}
+        SortOrder::Name => children.sort_unstable_by(|a, b| key(a).cmp(&key(b))),
+        SortOrder::Size => children.sort_unstable_by(|a, b| {
+            (Reverse(fs.node(*a).size()), key(a)).cmp(&(Reverse(fs.node(*b).size()), key(b)))
+        }),
+    }
+
+    children
+}
+
+fn describe(node: &Node) -> String {
+    let kind = if node.is_folder() { "dir" } else { "file" };
+    format!("{} ({}, size={})", node.name(), kind, node.size())
+}
+
+fn tree_text(fs: &FileSystem, sort: SortOrder) -> String {
+    let mut out = format!("/ (dir, size={})\n", fs.node(FileSystem::ROOT).size());
+
+    // the node, its depth below the root and if it's the last child of its parent
+    let mut stack = vec![(FileSystem::ROOT, 0, true)];
+    // for every ancestor of the current node if it was the last child
+    let mut last_ancestors = vec![];
+
+    while let Some((id, depth, last)) = stack.pop() {
+        if id != FileSystem::ROOT {
+            last_ancestors.truncate(depth - 1);
+
+            for &l in &last_ancestors {
+                out.push_str(if l { "    " } else { "│   " });
+            }
+
+            out.push_str(if last { "└── " } else { "├── " });
+            out.push_str(&describe(fs.node(id)));
+            out.push('\n');
+            last_ancestors.push(last);
+        }
+
+        let children = sorted_children(fs, id, sort);
+        let count = children.len();
+
+        stack.extend(
+            children
+                .into_iter()
+                .enumerate()
+                .rev()
+                .map(|(i, c)| (c, depth + 1, i + 1 == count)),
+        );
+    }
+
+    out
+}
+
+fn tree_json(fs: &FileSystem, sort: SortOrder) -> String {
+    enum Step {
+        Node(NodeId),
+        Separator,
+        Close,
+    }
+
+    let mut out = String::new();
+    let mut stack = vec![Step::Node(FileSystem::ROOT)];
+
+    // written without recursion, a folder is closed after all its children were written
+    while let Some(step) = stack.pop() {
+        let id = match step {
+            Step::Node(id) => id,
+            Step::Separator => {
+                out.push(',');
+                continue;
+            }
+            Step::Close => {
+                out.push_str("]}");
+                continue;
+            }
+        };
+
+        let node = fs.node(id);
+        let name = if id == FileSystem::ROOT {
+            "/"
+        } else {
+            node.name()
+        };
+
+        if !node.is_folder() {
+            out.push_str(&format!(
+                "{{\"name\":{},\"type\":\"file\",\"size\":{}}}",
+                json_string(name),
+                node.size()
+            ));
+            continue;
+        }
+
+        out.push_str(&format!(
+            "{{\"name\":{},\"type\":\"dir\",\"size\":{},\"children\":[",
+            json_string(name),
+            node.size()
+        ));
+        stack.push(Step::Close);
+
+        for (i, c) in sorted_children(fs, id, sort).into_iter().enumerate().rev() {
+            stack.push(Step::Node(c));
+
+            if i > 0 {
+                stack.push(Step::Separator);
+            }
+        }
+    }
+
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{du, tree, ListingFormat, SortOrder};
+    use crate::year_2022::day07::build_file_system;
+
+    const TRANSCRIPT: &[u8] =
+        b"$ cd /\n$ ls\ndir a\n14 b.txt\n$ cd a\n$ ls\n5 f\ndir e\n$ cd e\n$ ls\n3 i\n";
+
+    #[test]
+    fn trees_are_drawn_with_names_and_sizes() {
+        let fs = build_file_system(TRANSCRIPT).unwrap();
+
+        assert_eq!(
+            "\
+/ (dir, size=22)
+├── a (dir, size=8)
+│   ├── f (file, size=5)
+│   └── e (dir, size=3)
+│       └── i (file, size=3)
+└── b.txt (file, size=14)
+",
+            tree(&fs, SortOrder::Transcript, ListingFormat::Text)
+        );
+
+        assert_eq!(
+            "\
+/ (dir, size=22)
+├── b.txt (file, size=14)
+└── a (dir, size=8)
+    ├── f (file, size=5)
+    └── e (dir, size=3)
+        └── i (file, size=3)
+",
+            tree(&fs, SortOrder::Size, ListingFormat::Text)
+        );
+
+        assert_eq!(
+            concat!(
+                r#"{"name":"/","type":"dir","size":22,"children":["#,
+                r#"{"name":"a","type":"dir","size":8,"children":["#,
+                r#"{"name":"e","type":"dir","size":3,"children":[{"name":"i","type":"file","size":3}]},"#,
+                r#"{"name":"f","type":"file","size":5}]},"#,
+                r#"{"name":"b.txt","type":"file","size":14}]}"#,
+                "\n"
+            ),
+            tree(&fs, SortOrder::Name, ListingFormat::Json)
+        );
+    }
+
+    #[test]
+    fn du_lists_every_folder_with_its_path() {
+        let fs = build_file_system(TRANSCRIPT).unwrap();
+
+        assert_eq!(
+            "3\t/a/e\n8\t/a\n22\t/\n",
+            du(&fs, SortOrder::Transcript, ListingFormat::Text)
+        );
+        assert_eq!(
+            "22\t/\n8\t/a\n3\t/a/e\n",
+            du(&fs, SortOrder::Size, ListingFormat::Text)
+        );
+        assert_eq!(
+            "[{\"path\":\"/\",\"size\":22},{\"path\":\"/a\",\"size\":8},{\"path\":\"/a/e\",\"size\":3}]\n",
+            du(&fs, SortOrder::Name, ListingFormat::Json)
+        );
+    }
+}
@@ -0,0 +1,248 @@
+use crate::aoc_general::SolveError;
+
+use super::{FileSystem, NodeId};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FileType {
+    File,
+    Folder,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stat {
+    pub path: String,
+    pub file_type: FileType,
+    /// The cumulative size for folders.
+    pub size: u64,
+    /// The number of direct children, always `0` for files.
+    pub entries: usize,
+}
+
+impl FileSystem {
+    /// Resolves an absolute path or one relative to the folder `from`. `.` and empty components
+    /// are skipped and `..` at the root stays at the root, like in a shell.
+    pub fn resolve(&self, from: NodeId, path: &str) -> Option<NodeId> {
+        let mut current = if path.starts_with('/') {
+            Self::ROOT
+        } else {
+            from
+        };
+
+        for component in path.split('/') {
+            current = match component {
+                "" | "." => current,
+                ".." => self.node(current).parent().unwrap_or(Self::ROOT),
+                name => self.find_child(current, name)?,
+            };
+        }
+
+        Some(current)
+    }
+
+    pub fn stat(&self, from: NodeId, path: &str) -> Option<Stat> {
+        let id = self.resolve(from, path)?;
+        let node = self.node(id);
+
+        Some(Stat {
+            path: self.path(id),
+            file_type: if node.is_folder() {
+                FileType::Folder
+            } else {
+                FileType::File
+            },
+            size: node.size(),
+            entries: node.children().len(),
+        })
+    }
+
+    /// Files matching a pattern with `*` (any number of characters) and `?` (a single
+    /// character), both never matching a `/`. Patterns without a `/` are matched against the
+    /// file name, all others against the absolute path.
+    pub fn glob(&self, pattern: &str) -> Vec<NodeId> {
+        let pattern = pattern.chars().collect::<Vec<_>>();
+        let full_path = pattern.contains(&'/');
+
+        self.pre_order(Self::ROOT)
+            .filter(|&id| !self.node(id).is_folder())
+            .filter(|&id| {
+                let text = if full_path {
+                    self.path(id).chars().collect::<Vec<_>>()
+                } else {
+                    self.node(id).name().chars().collect()
+                };
+
+                glob_match(&pattern, &text)
+            })
+            .collect()
+    }
+
+    /// Folders whose cumulative size satisfies the predicate, in pre-order.
+    pub fn folders_where<'a, P>(&'a self, predicate: P) -> impl Iterator<Item = NodeId> + 'a
+    where
+        P: Fn(u64) -> bool + 'a,
+    {
+        self.pre_order(Self::ROOT)
+            .filter(move |&id| self.node(id).is_folder() && predicate(self.node(id).size()))
+    }
+
+    /// How much has to be deleted so that at least `required_free_space` of the disk is free.
+    pub fn space_to_free(
+        &self,
+        disk_size: u64,
+        required_free_space: u64,
+    ) -> Result<u64, SolveError> {
+        let used_space = self.node(Self::ROOT).size();
+        let free_space = disk_size.checked_sub(used_space).ok_or_else(|| {
+            SolveError::new(format!(
+                "used space of {} exceeds the disk size of {}",
+                used_space, disk_size
+            ))
+        })?;
+
+        Ok(required_free_space.saturating_sub(free_space))
+    }
+
+    /// The smallest folder whose deletion frees `required_free_space` on a disk of `disk_size`.
+    /// `None` if nothing has to be deleted or even deleting everything isn't enough.
+    pub fn deletion_target(
+        &self,
+        disk_size: u64,
+        required_free_space: u64,
+    ) -> Result<Option<NodeId>, SolveError> {
+        let to_free = self.space_to_free(disk_size, required_free_space)?;
+
+        if to_free == 0 {
+            return Ok(None);
+        }
+
+        Ok(self
+            .folders_where(move |size| size >= to_free)
+            .min_by_key(|&id| self.node(id).size()))
+    }
+}
+
+/// Matches without recursion by remembering only the last `*`, a later `*` can always cover
+/// what an earlier one would have matched since neither crosses a `/`.
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // the position of the last `*` and of the text it started to match at
+    let mut star = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some('?') if text[t] != '/' => {
+                p += 1;
+                t += 1;
+            }
+            Some(&c) if c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) if text[star_t] != '/' => {
+                    star = Some((star_p, star_t + 1));
+                    p = star_p + 1;
+                    t = star_t + 1;
+                }
+                _ => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{glob_match, FileType, Stat};
+    use crate::year_2022::day07::{build_file_system, FileSystem};
+
+    const EXAMPLE: &[u8] = include_bytes!("../../../data/2022/examples/07-input.txt");
+
+    fn matches(pattern: &str, text: &str) -> bool {
+        glob_match(
+            &pattern.chars().collect::<Vec<_>>(),
+            &text.chars().collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn paths_are_resolved_like_in_a_shell() {
+        let fs = build_file_system(EXAMPLE).unwrap();
+        let a = fs.resolve(FileSystem::ROOT, "a").unwrap();
+        let e = fs.resolve(a, "/a/e").unwrap();
+
+        assert_eq!("/a/e", fs.path(e));
+        assert_eq!(Some(a), fs.resolve(e, ".."));
+        assert_eq!(
+            fs.resolve(FileSystem::ROOT, "/d"),
+            fs.resolve(e, "../../d/")
+        );
+        assert_eq!(Some(FileSystem::ROOT), fs.resolve(e, "../../../.."));
+        assert_eq!(None, fs.resolve(a, "d"));
+        assert_eq!(None, fs.resolve(FileSystem::ROOT, "/b.txt/x"));
+
+        assert_eq!(
+            Some(Stat {
+                path: "/a".into(),
+                file_type: FileType::Folder,
+                size: 94853,
+                entries: 4,
+            }),
+            fs.stat(e, "./..")
+        );
+        assert_eq!(
+            Some(FileType::File),
+            fs.stat(a, "h.lst").map(|s| s.file_type)
+        );
+    }
+
+    #[test]
+    fn globs_match_file_names_or_paths() {
+        let fs = build_file_system(EXAMPLE).unwrap();
+        let paths = |pattern| {
+            fs.glob(pattern)
+                .into_iter()
+                .map(|id| fs.path(id))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(vec!["/d/d.log"], paths("*.log"));
+        assert_eq!(
+            vec!["/a/h.lst", "/b.txt", "/c.dat", "/d/d.log", "/d/d.ext"],
+            paths("?.???")
+        );
+        assert_eq!(vec!["/a/e/i"], paths("/a/*/*"));
+        assert!(paths("/*").iter().all(|p| p.matches('/').count() == 1));
+
+        assert!(matches("*", ""));
+        assert!(matches("a*b*c", "axxbyyc"));
+        assert!(!matches("a*c", "ab/c"));
+        assert!(!matches("?", "/"));
+    }
+
+    #[test]
+    fn deletion_targets_depend_on_the_disk() {
+        let fs = build_file_system(EXAMPLE).unwrap();
+        let target = |disk, required| {
+            fs.deletion_target(disk, required)
+                .map(|t| t.map(|id| fs.path(id)))
+        };
+
+        assert_eq!(Ok(Some("/d".into())), target(70_000_000, 30_000_000));
+        assert_eq!(Ok(Some("/a/e".into())), target(48_381_165, 500));
+        assert_eq!(Ok(None), target(100_000_000, 30_000_000));
+        assert_eq!(Ok(None), target(50_000_000, 60_000_000));
+        assert!(target(1_000, 0).is_err());
+
+        let small = fs.folders_where(|size| size <= 100_000).collect::<Vec<_>>();
+        assert_eq!(
+            vec!["/a", "/a/e"],
+            small.iter().map(|&id| fs.path(id)).collect::<Vec<_>>()
+        );
+    }
+}
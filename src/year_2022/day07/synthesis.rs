@@ -0,0 +1,218 @@
+//! Writing a `$ cd` and `$ ls` terminal transcript for a filesystem, the inverse of parsing one.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use super::{FileSystem, NodeId};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Traversal {
+    /// Every folder is listed before its subfolders, like a recursive shell session.
+    DepthFirst,
+    /// All folders of a level are listed before the next level.
+    BreadthFirst,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Navigation {
+    /// `cd ..` up to the closest common folder and down from there.
+    Shortest,
+    /// `cd /` and down from the root before every `ls`.
+    FromRoot,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SynthesisOptions {
+    pub traversal: Traversal,
+    pub navigation: Navigation,
+}
+
+impl Default for SynthesisOptions {
+    fn default() -> Self {
+        Self {
+            traversal: Traversal::DepthFirst,
+            navigation: Navigation::Shortest,
+        }
+    }
+}
+
+/// A transcript that lists every folder once, after its parent was listed, so it also passes
+/// the strict mode of [`super::transcript::parse`].
+pub fn synthesize(fs: &FileSystem, options: SynthesisOptions) -> String {
+    let folders = match options.traversal {
+        Traversal::DepthFirst => fs.pre_order(FileSystem::ROOT).collect::<Vec<_>>(),
+        Traversal::BreadthFirst => fs.breadth_first(FileSystem::ROOT).collect(),
+    };
+
+    let mut out = String::from("$ cd /\n");
+    let mut current = vec![FileSystem::ROOT];
+
+    for folder in folders.into_iter().filter(|&id| fs.node(id).is_folder()) {
+        let target = ancestors(fs, folder);
+
+        match options.navigation {
+            Navigation::Shortest => {
+                let common = current
+                    .iter()
+                    .zip(&target)
+                    .take_while(|(a, b)| a == b)
+                    .count();
+
+                for _ in common..current.len() {
+                    out.push_str("$ cd ..\n");
+                }
+
+                for &id in &target[common..] {
+                    out.push_str(&format!("$ cd {}\n", fs.node(id).name()));
+                }
+            }
+            Navigation::FromRoot if folder != FileSystem::ROOT => {
+                out.push_str("$ cd /\n");
+
+                for &id in &target[1..] {
+                    out.push_str(&format!("$ cd {}\n", fs.node(id).name()));
+                }
+            }
+            Navigation::FromRoot => {}
+        }
+
+        out.push_str("$ ls\n");
+
+        for &child in fs.node(folder).children() {
+            let node = fs.node(child);
+
+            if node.is_folder() {
+                out.push_str(&format!("dir {}\n", node.name()));
+            } else {
+                out.push_str(&format!("{} {}\n", node.size(), node.name()));
+            }
+        }
+
+        current = target;
+    }
+
+    out
+}
+
+/// The folders from the root down to `folder`, both included.
+fn ancestors(fs: &FileSystem, folder: NodeId) -> Vec<NodeId> {
+    let mut ids = vec![folder];
+
+    while let Some(parent) = fs.node(*ids.last().unwrap()).parent() {
+        ids.push(parent);
+    }
+
+    ids.reverse();
+    ids
+}
+
+/// Reads a local directory with everything below it, entries sorted by name. Symbolic links are
+/// skipped, names with line breaks can't be part of a transcript and are an error.
+pub fn scan_dir(root: &Path) -> io::Result<FileSystem> {
+    let mut fs = FileSystem::new();
+    let mut stack: Vec<(NodeId, PathBuf)> = vec![(FileSystem::ROOT, root.to_path_buf())];
+
+    while let Some((folder, path)) = stack.pop() {
+        let mut entries = fs::read_dir(&path)?.collect::<io::Result<Vec<_>>>()?;
+        entries.sort_by_key(|e| e.file_name());
+
+        fs.cd_to(folder);
+
+        for entry in entries {
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            if name.contains(['\n', '\r']) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{} contains a line break", entry.path().display()),
+                ));
+            }
+
+            let metadata = fs::symlink_metadata(entry.path())?;
+
+            if metadata.is_dir() {
+                let id = fs.find_or_create_subdirectory(&name);
+                stack.push((id, entry.path()));
+            } else if metadata.is_file() {
+                fs.add_file(&name, metadata.len());
+            }
+        }
+    }
+
+    fs.cd_to(FileSystem::ROOT);
+    fs.aggregate_sizes();
+
+    Ok(fs)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::{scan_dir, synthesize, Navigation, SynthesisOptions, Traversal};
+    use crate::year_2022::day07::{
+        listing::{self, ListingFormat, SortOrder},
+        transcript::{self, Mode},
+    };
+
+    const EXAMPLE: &[u8] = include_bytes!("../../../data/2022/examples/07-input.txt");
+
+    #[test]
+    fn the_example_is_synthesized_as_it_was_written() {
+        let fs = transcript::parse(EXAMPLE, Mode::Strict).unwrap().fs;
+
+        assert_eq!(
+            String::from_utf8_lossy(EXAMPLE).trim_end(),
+            synthesize(&fs, SynthesisOptions::default()).trim_end()
+        );
+    }
+
+    #[test]
+    fn every_option_rebuilds_the_same_filesystem() {
+        let fs = transcript::parse(EXAMPLE, Mode::Strict).unwrap().fs;
+        let tree = listing::tree(&fs, SortOrder::Name, ListingFormat::Text);
+
+        for traversal in [Traversal::DepthFirst, Traversal::BreadthFirst] {
+            for navigation in [Navigation::Shortest, Navigation::FromRoot] {
+                let options = SynthesisOptions {
+                    traversal,
+                    navigation,
+                };
+                let synthesized = synthesize(&fs, options);
+                let parsed = transcript::parse(synthesized.as_bytes(), Mode::Strict)
+                    .unwrap_or_else(|e| panic!("{:?}: {}\n{}", options, e, synthesized));
+
+                assert_eq!(
+                    tree,
+                    listing::tree(&parsed.fs, SortOrder::Name, ListingFormat::Text)
+                );
+            }
+        }
+
+        let breadth_first = SynthesisOptions {
+            traversal: Traversal::BreadthFirst,
+            navigation: Navigation::FromRoot,
+        };
+        assert!(synthesize(&fs, breadth_first).contains("$ cd /\n$ cd d\n$ ls\n"));
+        assert!(synthesize(&fs, breadth_first).contains("$ cd /\n$ cd a\n$ cd e\n$ ls\n"));
+    }
+
+    #[test]
+    fn local_directories_are_scanned() {
+        let dir = env::temp_dir().join(format!("aoc-day7-scan-{}", process::id()));
+        _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("a/e")).unwrap();
+        fs::write(dir.join("b.txt"), "0123456789").unwrap();
+        fs::write(dir.join("a/e/i"), "abc").unwrap();
+
+        let scanned = scan_dir(&dir);
+        _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(
+            "$ cd /\n$ ls\ndir a\n10 b.txt\n$ cd a\n$ ls\ndir e\n$ cd e\n$ ls\n3 i\n",
+            synthesize(&scanned.unwrap(), SynthesisOptions::default())
+        );
+    }
+}
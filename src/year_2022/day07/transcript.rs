@@ -0,0 +1,307 @@
+//! Reading the `$ cd` and `$ ls` terminal transcript, with checks for inconsistent transcripts.
+
+use std::{collections::HashMap, fmt};
+
+use crate::{aoc_general::SolveError, common::Scanner};
+
+use super::{FileSystem, NodeId};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Mode {
+    /// The first inconsistency is an error.
+    Strict,
+    /// Inconsistencies are collected, entries that were already listed are only added once (the
+    /// first listing wins) and unsupported commands are skipped with their output.
+    Lenient,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IssueKind {
+    /// A folder was listed again with other entries or sizes than the first time.
+    RelistedDifferently {
+        path: String,
+        first_line: usize,
+    },
+    /// `cd` into a folder that no `ls` of its parent showed.
+    CdIntoUnlisted {
+        path: String,
+    },
+    CdUpAtRoot,
+    /// The same name appears more than once in a single `ls` output.
+    DuplicateName {
+        path: String,
+    },
+    UnsupportedCommand(String),
+}
+
+impl fmt::Display for IssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IssueKind::RelistedDifferently { path, first_line } => write!(
+                f,
+                "{} is listed differently than by the ls on line {}",
+                path, first_line
+            ),
+            IssueKind::CdIntoUnlisted { path } => {
+                write!(f, "cd into {} which was never listed", path)
+            }
+            IssueKind::CdUpAtRoot => write!(f, "cd .. at the root folder"),
+            IssueKind::DuplicateName { path } => write!(f, "{} is listed more than once", path),
+            IssueKind::UnsupportedCommand(c) => write!(f, "unsupported command '{}'", c),
+        }
+    }
+}
+
+/// An inconsistency of the transcript at a (1-based) line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub line: usize,
+    pub kind: IssueKind,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+pub struct Transcript {
+    pub fs: FileSystem,
+    /// Always empty in [`Mode::Strict`].
+    pub issues: Vec<Issue>,
+}
+
+/// Rebuilds the filesystem from a transcript. Input that doesn't look like a transcript at all
+/// is an error in both modes.
+pub fn parse(input: &[u8], mode: Mode) -> Result<Transcript, SolveError> {
+    let mut parser = Parser {
+        scanner: Scanner::new(input),
+        fs: FileSystem::new(),
+        mode,
+        issues: vec![],
+        listings: HashMap::new(),
+    };
+
+    parser.parse()?;
+    parser.fs.aggregate_sizes();
+
+    Ok(Transcript {
+        fs: parser.fs,
+        issues: parser.issues,
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Entry {
+    Folder(String),
+    File(String, u64),
+}
+
+impl Entry {
+    fn name(&self) -> &str {
+        match self {
+            Entry::Folder(name) | Entry::File(name, _) => name,
+        }
+    }
+}
+
+struct Parser<'a> {
+    scanner: Scanner<'a>,
+    fs: FileSystem,
+    mode: Mode,
+    issues: Vec<Issue>,
+    /// The line and the sorted entries of the first `ls` of every folder.
+    listings: HashMap<NodeId, (usize, Vec<Entry>)>,
+}
+
+impl Parser<'_> {
+    fn parse(&mut self) -> Result<(), SolveError> {
+        while !self.scanner.is_eof() {
+            let line = self.scanner.line();
+            self.scanner.expect_literal(b"$ ")?;
+
+            if self.scanner.try_literal(b"cd ") {
+                let name = String::from_utf8_lossy(self.scanner.read_line()).to_string();
+                self.cd(line, &name)?;
+            } else if self.scanner.peek_line() == b"ls" {
+                self.scanner.read_line();
+                self.ls(line)?;
+            } else {
+                let command = String::from_utf8_lossy(self.scanner.read_line()).to_string();
+                self.report(line, IssueKind::UnsupportedCommand(command))?;
+                self.skip_output();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn report(&mut self, line: usize, kind: IssueKind) -> Result<(), SolveError> {
+        match self.mode {
+            Mode::Strict => Err(SolveError::new(kind.to_string()).at_line(line)),
+            Mode::Lenient => {
+                self.issues.push(Issue { line, kind });
+                Ok(())
+            }
+        }
+    }
+
+    fn skip_output(&mut self) {
+        while !self.scanner.is_eof() && !self.scanner.starts_with(b"$") {
+            self.scanner.read_line();
+        }
+    }
+
+    fn cd(&mut self, line: usize, name: &str) -> Result<(), SolveError> {
+        let working_dir = self.fs.working_dir();
+
+        match name {
+            "/" => {}
+            ".." if working_dir == FileSystem::ROOT => {
+                self.report(line, IssueKind::CdUpAtRoot)?;
+            }
+            ".." => {}
+            _ if self.fs.find_subdirectory(working_dir, name).is_none() => {
+                let path = self.child_path(working_dir, name);
+                self.report(line, IssueKind::CdIntoUnlisted { path })?;
+            }
+            _ => {}
+        }
+
+        self.fs.cd(name);
+        Ok(())
+    }
+
+    /// Reads the output of `ls` up to the next command.
+    fn ls(&mut self, line: usize) -> Result<(), SolveError> {
+        let folder = self.fs.working_dir();
+        let mut entries: Vec<Entry> = vec![];
+
+        while !self.scanner.is_eof() && !self.scanner.starts_with(b"$") {
+            let entry_line = self.scanner.line();
+
+            let entry = if self.scanner.try_literal(b"dir ") {
+                Entry::Folder(String::from_utf8_lossy(self.scanner.read_line()).to_string())
+            } else {
+                let size = self.scanner.read_uint::<u64>()?;
+                self.scanner.expect_literal(b" ")?;
+                let name = String::from_utf8_lossy(self.scanner.read_line()).to_string();
+                Entry::File(name, size)
+            };
+
+            if entries.iter().any(|e| e.name() == entry.name()) {
+                let path = self.child_path(folder, entry.name());
+                self.report(entry_line, IssueKind::DuplicateName { path })?;
+                continue;
+            }
+
+            entries.push(entry);
+        }
+
+        let mut sorted = entries.clone();
+        sorted.sort_unstable();
+
+        match self.listings.get(&folder) {
+            None => {
+                self.listings.insert(folder, (line, sorted));
+            }
+            Some((first_line, first)) if *first != sorted => {
+                let kind = IssueKind::RelistedDifferently {
+                    path: self.fs.path(folder),
+                    first_line: *first_line,
+                };
+                self.report(line, kind)?;
+            }
+            Some(_) => {}
+        }
+
+        for entry in entries {
+            if self.fs.find_child(folder, entry.name()).is_some() {
+                continue;
+            }
+
+            match entry {
+                Entry::Folder(name) => _ = self.fs.find_or_create_subdirectory(&name),
+                Entry::File(name, size) => _ = self.fs.add_file(&name, size),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn child_path(&self, folder: NodeId, name: &str) -> String {
+        match self.fs.path(folder).as_str() {
+            "/" => format!("/{}", name),
+            parent => format!("{}/{}", parent, name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, Issue, IssueKind, Mode};
+
+    const INCONSISTENT: &[u8] = b"\
+$ cd /
+$ ls
+dir a
+10 b
+10 b
+$ cd ..
+$ cd x
+$ ls
+4 y
+$ cd /
+$ ls
+dir a
+dir x
+10 b
+$ pwd
+/
+$ cd a
+$ ls
+5 c
+$ ls
+6 c
+";
+
+    #[test]
+    fn lenient_mode_reports_issues_and_counts_entries_once() {
+        let transcript = parse(INCONSISTENT, Mode::Lenient).unwrap();
+
+        let issues = transcript
+            .issues
+            .iter()
+            .map(Issue::to_string)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                "line 5: /b is listed more than once",
+                "line 6: cd .. at the root folder",
+                "line 7: cd into /x which was never listed",
+                "line 11: / is listed differently than by the ls on line 2",
+                "line 15: unsupported command 'pwd'",
+                "line 20: /a is listed differently than by the ls on line 18",
+            ],
+            issues
+        );
+
+        // b, y and the first c
+        assert_eq!(19, transcript.fs.node(super::FileSystem::ROOT).size());
+    }
+
+    #[test]
+    fn strict_mode_rejects_the_first_issue() {
+        let error = parse(INCONSISTENT, Mode::Strict).err().unwrap();
+
+        assert_eq!(Some(5), error.line);
+        assert_eq!(
+            IssueKind::DuplicateName { path: "/b".into() }.to_string(),
+            error.reason
+        );
+
+        assert!(parse(b"$ cd /\n$ ls\ndir a\n$ cd a\n$ cd ..\n", Mode::Strict).is_ok());
+        assert!(parse(b"$ cd /\n$ ls\nabc\n", Mode::Lenient).is_err());
+    }
+}
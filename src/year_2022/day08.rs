@@ -1,130 +1,109 @@
-use std::vec;
-
 use crate::{
-    aoc_general::{PuzzlePart, PuzzleSolver},
-    common::{NormalizeLineBreaks, RangeIteratorInclusive},
+    aoc_general::{Answer, PuzzleSolver, SolveError},
+    common::{Direction, Grid},
 };
 
 #[derive(Default)]
 pub struct Day8;
 
 impl PuzzleSolver for Day8 {
-    fn solve(&self, input: &mut dyn Iterator<Item = u8>, _part: PuzzlePart) -> String {
-        let mut forest = build_forest(input);
+    fn year(&self) -> u16 {
+        2022
+    }
 
-        match _part {
-            PuzzlePart::Part1 => {
-                forest.mark_all_visible_trees();
-                forest.count_visible_trees().to_string()
-            }
-            PuzzlePart::Part2 => forest.get_max_scenic_score().to_string(),
-        }
+    fn day(&self) -> u8 {
+        8
     }
-}
 
-fn build_forest(input: &mut dyn Iterator<Item = u8>) -> Forest2D {
-    let chars = input.normalize_line_breaks();
+    type Model = Forest2D;
 
-    let mut trees = vec![];
+    fn parse(&self, input: &[u8]) -> Result<Self::Model, SolveError> {
+        build_forest(input)
+    }
 
-    let mut current_width = 0;
-    let mut reference_width = None;
-    let mut height = 0;
+    fn part1(&self, forest: &Self::Model) -> Result<Answer, SolveError> {
+        Ok(forest.count_visible_trees().into())
+    }
 
-    for c in chars {
-        if c == b'\n' {
-            match reference_width {
-                None => reference_width = Some(current_width),
-                Some(w) => assert!(w == current_width),
-            };
+    fn part2(&self, forest: &Self::Model) -> Result<Answer, SolveError> {
+        Ok(forest.get_max_scenic_score().into())
+    }
+}
 
-            current_width = 0;
-            height += 1;
-            continue;
+fn build_forest(input: &[u8]) -> Result<Forest2D, SolveError> {
+    let trees = Grid::parse(input, |c| {
+        if c.is_ascii_digit() {
+            Ok(c - b'0') // convert ASCII to integer
+        } else {
+            Err(SolveError::new(format!(
+                "expected a tree height, found '{}'",
+                c as char
+            )))
         }
+    })?;
 
-        current_width += 1;
-        let c = c - b'0'; // convert ASCII to integer
-        assert!(c <= 9);
-
-        trees.push(Tree {
-            height: c,
-            visible: false,
-        });
-    }
-
-    Forest2D {
-        trees,
-        height,
-        width: reference_width.unwrap(),
+    if trees.width() == 0 {
+        return Err(SolveError::new("forest should contain at least one tree"));
     }
-}
 
-struct Tree {
-    height: u8,
-    visible: bool,
+    Ok(Forest2D { trees })
 }
 
-struct Forest2D {
-    trees: Vec<Tree>,
-    height: usize,
-    width: usize,
+pub struct Forest2D {
+    /// Height of every tree.
+    trees: Grid<u8>,
 }
 
 impl Forest2D {
-    pub fn mark_all_visible_trees(&mut self) {
-        // iterate rows and check left to right and right to left
-        for y in 0..self.height {
-            let left_to_right = self.direction_index_iter(0, y, Direction::Right);
-            let right_to_left = self.direction_index_iter(self.width - 1, y, Direction::Left);
-
-            self.mark_visible(left_to_right);
-            self.mark_visible(right_to_left);
+    /// Returns for every tree (in row-major order) if it's visible from outside of the forest.
+    fn find_visible_trees(&self) -> Vec<bool> {
+        let width = self.trees.width();
+        let height = self.trees.height();
+
+        let mut visible = vec![false; width * height];
+
+        // look into every row from the left and right edge, and into every column
+        // from the top and bottom edge
+        let rays = (0..height)
+            .flat_map(|y| [(0, y, Direction::Right), (width - 1, y, Direction::Left)])
+            .chain(
+                (0..width).flat_map(|x| [(x, 0, Direction::Down), (x, height - 1, Direction::Up)]),
+            );
+
+        for (x, y, direction) in rays {
+            if let Some(indices) = self.trees.ray_indices(x, y, direction) {
+                self.mark_visible(&mut visible, indices);
+            }
         }
 
-        // iterate columns and check top to bottom and bottom to top
-        for x in 0..self.width {
-            let top_to_bottom = self.direction_index_iter(x, 0, Direction::Down);
-            let bottom_to_top = self.direction_index_iter(x, self.height - 1, Direction::Up);
-
-            self.mark_visible(top_to_bottom);
-            self.mark_visible(bottom_to_top);
-        }
+        visible
     }
 
-    fn mark_visible<R: Iterator<Item = usize>>(&mut self, index_range: R) {
+    fn mark_visible<R: Iterator<Item = usize>>(&self, visible: &mut [bool], index_range: R) {
         let mut max_tree_height = None;
 
         for i in index_range {
-            let tree = &mut self.trees[i];
+            let tree_height = self.trees.cells()[i];
 
-            match max_tree_height {
-                None => {
-                    max_tree_height = Some(tree.height);
-                    tree.visible = true;
-                }
-                Some(h) => {
-                    if tree.height > h {
-                        tree.visible = true;
-                        max_tree_height = Some(tree.height);
-                    }
-                }
+            if max_tree_height.is_none_or(|h| tree_height > h) {
+                visible[i] = true;
+                max_tree_height = Some(tree_height);
             }
         }
     }
 
     pub fn count_visible_trees(&self) -> u32 {
-        self.trees
+        self.find_visible_trees()
             .iter()
-            .map(|t| if t.visible { 1 } else { 0 })
+            .map(|&v| if v { 1 } else { 0 })
             .sum()
     }
 
     pub fn get_max_scenic_score(&self) -> u32 {
         let mut max_score = 0;
 
-        for x in 1..self.width - 1 {
-            for y in 1..self.height - 1 {
+        for x in 1..self.trees.width().saturating_sub(1) {
+            for y in 1..self.trees.height().saturating_sub(1) {
                 let score = self.get_scenic_score_for_position(x, y);
                 if score > max_score {
                     max_score = score;
@@ -136,69 +115,30 @@ impl Forest2D {
     }
 
     fn get_scenic_score_for_position(&self, x: usize, y: usize) -> u32 {
-        let vd_right = self.count_trees_in_line(self.direction_index_iter(x, y, Direction::Right));
-        let vd_left = self.count_trees_in_line(self.direction_index_iter(x, y, Direction::Left));
-        let vd_up = self.count_trees_in_line(self.direction_index_iter(x, y, Direction::Up));
-        let vd_down = self.count_trees_in_line(self.direction_index_iter(x, y, Direction::Down));
-
-        return vd_right * vd_left * vd_down * vd_up;
+        Direction::ORTHOGONAL
+            .into_iter()
+            .map(|d| count_trees_in_line(self.trees.ray_values(x, y, d)))
+            .product()
     }
+}
 
-    fn count_trees_in_line<R: Iterator<Item = usize>>(&self, index_range: R) -> u32 {
-        let mut tree_height_iter = index_range.map(|i| self.trees[i].height);
-        let my_tree_height = tree_height_iter.next().unwrap();
+/// Counts the trees visible from the first tree of the line, the view ends at the
+/// first tree that is at least as high.
+fn count_trees_in_line<'a, I: Iterator<Item = &'a u8>>(mut line: I) -> u32 {
+    let my_tree_height = match line.next() {
+        Some(&h) => h,
+        None => return 0,
+    };
 
-        let mut visible_trees = 0;
+    let mut visible_trees = 0;
 
-        for height in tree_height_iter {
-            visible_trees += 1;
+    for &height in line {
+        visible_trees += 1;
 
-            if height >= my_tree_height {
-                break;
-            }
+        if height >= my_tree_height {
+            break;
         }
-
-        visible_trees
     }
 
-    fn direction_index_iter(
-        &self,
-        x: usize,
-        y: usize,
-        direction: Direction,
-    ) -> RangeIteratorInclusive<usize> {
-        let start = y * self.width + x;
-
-        match direction {
-            Direction::Right => RangeIteratorInclusive::new(start, self.get_right(y), 1),
-            Direction::Left => RangeIteratorInclusive::new(start, self.get_left(y), 1),
-            Direction::Down => RangeIteratorInclusive::new(start, self.get_bottom(x), self.width),
-            Direction::Up => RangeIteratorInclusive::new(start, self.get_top(x), self.width),
-        }
-        .unwrap()
-    }
-
-    fn get_left(&self, row: usize) -> usize {
-        self.width * row
-    }
-
-    fn get_right(&self, row: usize) -> usize {
-        self.width * (row + 1) - 1
-    }
-
-    fn get_top(&self, column: usize) -> usize {
-        column
-    }
-
-    fn get_bottom(&self, column: usize) -> usize {
-        column + (self.height - 1) * self.width
-    }
-}
-
-#[derive(Clone, Copy, PartialEq, Eq)]
-enum Direction {
-    Left,
-    Right,
-    Down,
-    Up,
+    visible_trees
 }
@@ -1,3 +1,5 @@
+//! Solvers of [Advent of Code 2022](https://adventofcode.com/2022).
+
 pub mod day01;
 pub mod day02;
 pub mod day03;
@@ -6,3 +8,47 @@ pub mod day05;
 pub mod day06;
 pub mod day07;
 pub mod day08;
+
+use crate::aoc_general::{SolveError, YearSolverCollection};
+
+/// New days are added here and above by `aoc2022 new-day`.
+pub fn solvers() -> Result<YearSolverCollection, SolveError> {
+    let mut y2022 = YearSolverCollection::new(2022);
+
+    y2022.add::<day01::Day1>()?;
+    y2022.add::<day02::Day2>()?;
+    y2022.add::<day03::Day3>()?;
+    y2022.add::<day04::Day4>()?;
+    y2022.add::<day05::Day5>()?;
+    y2022.add::<day06::Day6>()?;
+    y2022.add::<day07::Day7>()?;
+    y2022.add::<day08::Day8>()?;
+
+    Ok(y2022)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    #[test]
+    fn every_day_module_is_registered() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/src/year_2022");
+
+        let mut modules = fs::read_dir(dir)
+            .unwrap()
+            .filter_map(|e| {
+                let name = e.unwrap().file_name().to_string_lossy().to_string();
+                name.strip_prefix("day")?
+                    .strip_suffix(".rs")?
+                    .parse::<u8>()
+                    .ok()
+            })
+            .collect::<Vec<_>>();
+
+        modules.sort_unstable();
+
+        let registered = super::solvers().unwrap().days().collect::<Vec<_>>();
+        assert_eq!(modules, registered);
+    }
+}
@@ -0,0 +1,7 @@
+//! Fixtures shared by the integration tests.
+
+// the unit tests of the crate use the same stand-in server
+#[path = "../../src/test_support.rs"]
+mod test_support;
+
+pub use test_support::{fake_server, ok};
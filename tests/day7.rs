@@ -0,0 +1,48 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+#[test]
+fn du_lists_the_folders_of_the_example_transcript() {
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc2022"))
+        .args(["day7", "du", "--sort", "size"])
+        .arg("--input")
+        .arg(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/data/2022/examples/07-input.txt"
+        ))
+        .output()
+        .expect("binary should be runnable");
+
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(
+        "48381165\t/\n24933642\t/d\n94853\t/a\n584\t/a/e\n",
+        String::from_utf8_lossy(&output.stdout)
+    );
+}
+
+#[test]
+fn check_reports_inconsistencies_with_line_numbers() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_aoc2022"))
+        .args(["day7", "check"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("binary should be runnable");
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"$ cd /\n$ ls\n1 a\n$ cd b\n$ cd ..\n$ cd ..\n")
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+
+    assert!(!output.status.success());
+    assert_eq!(
+        "line 4: cd into /b which was never listed\nline 6: cd .. at the root folder\n",
+        String::from_utf8_lossy(&output.stdout)
+    );
+}
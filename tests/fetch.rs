@@ -0,0 +1,44 @@
+mod common;
+
+use std::{
+    env, fs,
+    process::{self, Command},
+};
+
+use common::{fake_server, ok};
+
+#[test]
+fn fetch_downloads_into_the_user_directory_only_once() {
+    let dir = env::temp_dir().join(format!("aoc-fetch-cli-{}", process::id()));
+    _ = fs::remove_dir_all(&dir);
+
+    let (base_url, server) = fake_server(vec![ok("A Y\nB X\n")]);
+
+    let fetch = || {
+        Command::new(env!("CARGO_BIN_EXE_aoc2022"))
+            .args(["fetch", "2", "--year", "2022", "--user", "someone"])
+            .arg("--data")
+            .arg(dir.join("data"))
+            .arg("--cache-dir")
+            .arg(dir.join("cache"))
+            .args(["--base-url", &base_url, "--request-interval", "0"])
+            .env("AOC_SESSION", "53616c7465645f5f")
+            .output()
+            .expect("binary should be runnable")
+    };
+
+    let first = fetch();
+    let request = server.join().unwrap().remove(0);
+    let second = fetch();
+
+    let input = fs::read_to_string(dir.join("data/2022/someone/02-input.txt"));
+    _ = fs::remove_dir_all(&dir);
+
+    assert!(first.status.success(), "{:?}", first);
+    assert!(request.starts_with("GET /2022/day/2/input HTTP/1.1\r\n"));
+    assert!(request.contains("cookie: session=53616c7465645f5f\r\n"));
+    assert_eq!("A Y\nB X\n", input.unwrap());
+
+    assert!(second.status.success(), "{:?}", second);
+    assert!(String::from_utf8_lossy(&second.stdout).contains("is already on disk"));
+}
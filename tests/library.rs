@@ -0,0 +1,31 @@
+use std::fs;
+
+use aoc2022::aoc_general::{Answer, PuzzlePart};
+
+#[test]
+fn solvers_can_be_used_without_the_command_line_tool() {
+    let registry = aoc2022::registry().unwrap();
+    let solvers = registry.year(2022).unwrap();
+
+    assert_eq!(
+        (1..=8).collect::<Vec<_>>(),
+        solvers.days().collect::<Vec<_>>()
+    );
+
+    let input = fs::read(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/data/2022/examples/08-input.txt"
+    ))
+    .unwrap();
+
+    let parsed = solvers.parse(8, &input).unwrap();
+
+    assert_eq!(
+        Ok(Answer::from(21u32)),
+        solvers.solve_parsed(&parsed, PuzzlePart::Part1)
+    );
+    assert_eq!(
+        Ok(Answer::from(8u32)),
+        solvers.solve_parsed(&parsed, PuzzlePart::Part2)
+    );
+}
@@ -0,0 +1,61 @@
+use std::process::Command;
+
+#[test]
+fn run_all_prints_a_row_per_part_and_totals() {
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc2022"))
+        .arg("run-all")
+        .args(["--data", "data/2022/examples"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .expect("binary should be runnable");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "run-all failed:\n{stdout}");
+    assert!(
+        stdout.lines().any(|l| l.starts_with("2022    8     2  8 ")),
+        "missing row for day 8 part 2:\n{stdout}"
+    );
+    assert!(
+        stdout.contains(" 0 failed, 0 errors"),
+        "unexpected summary:\n{stdout}"
+    );
+    assert!(stdout.contains("total time: "), "missing totals:\n{stdout}");
+}
+
+#[test]
+fn a_second_run_takes_the_answers_from_the_cache() {
+    let cache_dir = std::env::temp_dir().join(format!("aoc-run-all-cache-{}", std::process::id()));
+    _ = std::fs::remove_dir_all(&cache_dir);
+
+    let run = || {
+        let output = Command::new(env!("CARGO_BIN_EXE_aoc2022"))
+            .arg("run-all")
+            .args(["--data", "data/2022/examples", "--jobs", "2", "--cache"])
+            .arg("--cache-dir")
+            .arg(&cache_dir)
+            .current_dir(env!("CARGO_MANIFEST_DIR"))
+            .output()
+            .expect("binary should be runnable");
+
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+
+    let first = run();
+    let second = run();
+    _ = std::fs::remove_dir_all(&cache_dir);
+
+    assert!(
+        !first.contains("cached answers"),
+        "unexpected cache hits:\n{first}"
+    );
+    assert!(
+        second.contains("cached answers: 12"),
+        "missing cache hits:\n{second}"
+    );
+    assert!(
+        second.contains(" 0 failed, 0 errors"),
+        "unexpected summary:\n{second}"
+    );
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use std::{
+    env, fs,
+    path::Path,
+    process::{self, Command, Output},
+};
+
+use common::{fake_server, ok};
+
+fn submit(dir: &Path, base_url: &str, part: &str) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aoc2022"))
+        .args(["submit", "1", part, "--year", "2022", "--user", "someone"])
+        .arg("--data")
+        .arg(dir.join("data"))
+        .arg("--cache-dir")
+        .arg(dir.join("cache"))
+        .args(["--base-url", base_url, "--request-interval", "0"])
+        .env("AOC_SESSION", "53616c7465645f5f")
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .expect("binary should be runnable")
+}
+
+#[test]
+fn correct_answers_are_saved_and_wrong_ones_not_submitted_twice() {
+    let dir = env::temp_dir().join(format!("aoc-submit-cli-{}", process::id()));
+    _ = fs::remove_dir_all(&dir);
+
+    let user_dir = dir.join("data/2022/someone");
+    fs::create_dir_all(&user_dir).unwrap();
+    fs::copy(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("data/2022/examples/01-input.txt"),
+        user_dir.join("01-input.txt"),
+    )
+    .unwrap();
+
+    let (base_url, server) = fake_server(vec![
+        ok("<main><article><p>That's the right answer!  You are <em>one gold star</em> closer.</p></article></main>"),
+        ok("<main><article><p>That's not the right answer; your answer is too low.  Please wait one minute before trying again.</p></article></main>"),
+    ]);
+
+    let correct = submit(&dir, &base_url, "1");
+    let wrong = submit(&dir, &base_url, "2");
+    let requests = server.join().unwrap();
+
+    // both are refused without a request, the fake server is gone
+    let solved_again = submit(&dir, &base_url, "1");
+    let wrong_again = submit(&dir, &base_url, "2");
+
+    let output = fs::read_to_string(user_dir.join("01-output.txt"));
+    let log = fs::read_to_string(user_dir.join("submissions.tsv")).unwrap_or_default();
+    _ = fs::remove_dir_all(&dir);
+
+    assert!(correct.status.success(), "{:?}", correct);
+    assert!(requests[0].starts_with("POST /2022/day/1/answer HTTP/1.1\r\n"));
+    assert!(requests[0].ends_with("\r\n\r\nlevel=1&answer=24000"));
+    assert!(requests[1].ends_with("\r\n\r\nlevel=2&answer=45000"));
+    assert_eq!("24000\n", output.unwrap());
+
+    assert!(!wrong.status.success());
+    assert!(String::from_utf8_lossy(&wrong.stdout).contains("too-low: That's not the right answer"));
+
+    let log = log.lines().collect::<Vec<_>>();
+    assert_eq!(2, log.len());
+    assert!(log[0].ends_with("\t1\t1\tcorrect\t\t24000"), "{}", log[0]);
+    assert!(log[1].contains("\t1\t2\ttoo-low\t"), "{}", log[1]);
+
+    assert!(!solved_again.status.success());
+    assert!(String::from_utf8_lossy(&solved_again.stderr)
+        .contains("Not submitted: part 1 was already solved with 24000"));
+    assert!(!wrong_again.status.success());
+    assert!(String::from_utf8_lossy(&wrong_again.stderr).contains("Not submitted: "));
+}
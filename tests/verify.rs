@@ -0,0 +1,22 @@
+use std::process::Command;
+
+#[test]
+fn all_expected_outputs_in_data_match_the_solvers() {
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc2022"))
+        .arg("verify")
+        .args(["--data", "data"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .expect("binary should be runnable");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        output.status.success(),
+        "verify reported failures:\n{stdout}"
+    );
+    assert!(
+        stdout.contains(" 0 failed, 0 errors"),
+        "unexpected summary:\n{stdout}"
+    );
+}